@@ -0,0 +1,27 @@
+use std::process::Command;
+
+// embeds the git revision the binary was built from, so run history can be tied back to it.
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+
+    let rev = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|rev| rev.trim().to_string());
+
+    let dirty = Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .output()
+        .ok()
+        .map(|out| !out.stdout.is_empty())
+        .unwrap_or(false);
+
+    if let Some(rev) = rev {
+        let suffix = if dirty { "-dirty" } else { "" };
+        println!("cargo:rustc-env=ADVENT_GIT_REV={}{}", rev, suffix);
+    }
+}
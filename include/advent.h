@@ -0,0 +1,44 @@
+/* Generated by `header()` in src/ffi/mod.rs, edit that instead. */
+#ifndef ADVENT_H
+#define ADVENT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum AdventStatus {
+    /* `text` holds the answer */
+    ADVENT_OK = 0,
+    /* only 2022 is supported */
+    ADVENT_INVALID_YEAR = 1,
+    /* `day` must be 1-25 */
+    ADVENT_INVALID_DAY = 2,
+    /* `part` must be 1 or 2 */
+    ADVENT_INVALID_PART = 3,
+    /* the input was NULL or not valid UTF-8 */
+    ADVENT_INVALID_INPUT = 4,
+    /* the solver failed (or isn't implemented yet), `text` holds the reason */
+    ADVENT_SOLVER_PANICKED = 5,
+} AdventStatus;
+
+/* `text` is the answer on success, or an error message otherwise.
+   it's owned by the caller and must be released with `advent_free`. */
+typedef struct AdventAnswer {
+    AdventStatus status;
+    char *text;
+} AdventAnswer;
+
+/* Solves `part` of `day` for the puzzle input in `input_ptr[..len]` (UTF-8 text). */
+AdventAnswer advent_solve(uint32_t year, uint32_t day, uint32_t part, const uint8_t *input_ptr, size_t len);
+
+/* Releases the `text` of an `AdventAnswer`. */
+void advent_free(char *text);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ADVENT_H */
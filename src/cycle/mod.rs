@@ -0,0 +1,163 @@
+//! Finding where a simulation starts repeating itself, so a huge number of
+//! iterations can be answered without running them all.
+
+use std::{collections::HashMap, hash::Hash};
+
+use crate::math::Checked;
+
+/// A repeating run of states: the state at `start` is seen again `len` steps later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// how many steps run before the first repeating state.
+    pub start: usize,
+    pub len: usize,
+}
+
+/// A value that can be summed over steps (ie: height gained, or a count per monkey).
+/// the integer impls panic on overflow rather than wrapping, as a wrapped answer looks just like a real one.
+pub trait Accumulate: Clone + Default {
+    fn add(&self, other: &Self) -> Self;
+    /// the value accumulated by `n` repeats of `self`.
+    fn times(&self, n: u64) -> Self;
+}
+
+macro_rules! accumulate_int {
+    ($($t:ty),*) => {$(
+        impl Accumulate for $t {
+            fn add(&self, other: &Self) -> Self {
+                self.add_checked(*other).unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            fn times(&self, n: u64) -> Self {
+                let n = <$t>::try_from(n)
+                    .unwrap_or_else(|_| panic!("arithmetic overflow, {} repeats don't fit in {}", n, stringify!($t)));
+                self.mul_checked(n).unwrap_or_else(|e| panic!("{}", e))
+            }
+        }
+    )*};
+}
+
+accumulate_int!(u64, usize, i64);
+
+/// adds element-wise, treating missing elements as the default.
+impl<T: Accumulate> Accumulate for Vec<T> {
+    fn add(&self, other: &Self) -> Self {
+        let len = self.len().max(other.len());
+        (0..len)
+            .map(|i| match (self.get(i), other.get(i)) {
+                (Some(a), Some(b)) => a.add(b),
+                (Some(v), None) | (None, Some(v)) => v.clone(),
+                (None, None) => unreachable!(),
+            })
+            .collect()
+    }
+
+    fn times(&self, n: u64) -> Self {
+        self.iter().map(|v| v.times(n)).collect()
+    }
+}
+
+/// steps from `initial` until a state's fingerprint repeats.
+/// this never returns if the states don't repeat, so the fingerprint must only
+/// capture what decides the future (ie: a surface profile, not the whole tower).
+pub fn detect<S, K>(initial: S, mut step: impl FnMut(&S) -> S, mut fingerprint: impl FnMut(&S) -> K) -> Cycle
+where
+    K: Eq + Hash,
+{
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut state = initial;
+
+    for i in 0.. {
+        if let Some(start) = seen.insert(fingerprint(&state), i) {
+            return Cycle { start, len: i - start };
+        }
+        state = step(&state);
+    }
+    unreachable!()
+}
+
+/// the sum of the values produced by the first `iterations` steps from `initial`.
+/// `step` returns the next state along with the value produced by that step.
+/// once a fingerprint repeats, the remaining steps are extrapolated from the cycle.
+pub fn extrapolate<S, K, V>(
+    initial: S,
+    iterations: u64,
+    mut step: impl FnMut(&S) -> (S, V),
+    mut fingerprint: impl FnMut(&S) -> K,
+) -> V
+where
+    K: Eq + Hash,
+    V: Accumulate,
+{
+    let sum = |values: &[V]| values.iter().fold(V::default(), |acc, v| acc.add(v));
+
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut values: Vec<V> = Vec::new();
+    let mut state = initial;
+
+    while (values.len() as u64) < iterations {
+        if let Some(start) = seen.insert(fingerprint(&state), values.len()) {
+            let cycle = &values[start..];
+            let remaining = iterations - start as u64;
+            let (repeats, rest) = (remaining / cycle.len() as u64, (remaining % cycle.len() as u64) as usize);
+
+            return sum(&values[..start])
+                .add(&sum(cycle).times(repeats))
+                .add(&sum(&cycle[..rest]));
+        }
+
+        let (next, value) = step(&state);
+        values.push(value);
+        state = next;
+    }
+
+    sum(&values)
+}
+
+
+#[test]
+fn test_detect() {
+    // 3 -> 10 -> 101 % 13 = 10 ... so: 3, 10, 10 (start 1, len 1)
+    let cycle = detect(3_u64, |x| (x * x + 1) % 13, |x| *x);
+    assert_eq!(cycle, Cycle { start: 1, len: 1 });
+
+    // 0, 1, 2, 3, 4, 5, 3, 4, 5, ...
+    let cycle = detect(0_u64, |x| if *x == 5 { 3 } else { x + 1 }, |x| *x);
+    assert_eq!(cycle, Cycle { start: 3, len: 3 });
+}
+
+#[test]
+fn test_extrapolate() {
+    // each step adds the state it leaves, states go 0, 1, 2, 3, 4, 5, 3, 4, 5, ...
+    let step = |x: &u64| (if *x == 5 { 3 } else { x + 1 }, *x);
+    let brute_force = |n: u64| {
+        let mut x = 0;
+        (0..n).map(|_| { let (next, v) = step(&x); x = next; v }).sum::<u64>()
+    };
+
+    for n in [0, 2, 6, 7, 100, 1001] {
+        assert_eq!(extrapolate(0_u64, n, step, |x| *x), brute_force(n), "n = {}", n);
+    }
+    assert_eq!(extrapolate(0_u64, 1_000_000_000_000, step, |x| *x), 3_999_999_999_990);
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow in '3 * 6148914691236517206'")]
+fn test_accumulate_overflow() {
+    // wraps to 2 if unchecked
+    let _ = 3_u64.times(u64::MAX / 3 + 1);
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow, 9223372036854775808 repeats don't fit in i64")]
+fn test_accumulate_repeats_overflow() {
+    let _ = 0_i64.times(1 << 63);
+}
+
+#[test]
+fn test_accumulate_vec() {
+    let a = vec![1_u64, 2];
+    let b = vec![10_u64, 20, 30];
+    assert_eq!(a.add(&b), vec![11, 22, 30]);
+    assert_eq!(b.times(3), vec![30, 60, 90]);
+}
@@ -1,5 +1,7 @@
 use std::array::from_fn;
 use crate::Part;
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
 
 const N_ELVES_PT1: usize = 1;
 const N_ELVES_PT2: usize = 3;
@@ -7,28 +9,41 @@ const N_ELVES_PT2: usize = 3;
 pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
     
     let total = match part {
-        Part::Part1 => get_top_n::<{N_ELVES_PT1+1}>(input),
-        Part::Part2 => get_top_n::<{N_ELVES_PT2+1}>(input),
+        Part::Part1 => get_top_n::<{N_ELVES_PT1+1}>(input, &mut |_| {}),
+        Part::Part2 => get_top_n::<{N_ELVES_PT2+1}>(input, &mut |_| {}),
     };
     format!("{}", total)
 }
 
+/// emits the running answer every time an elf's group of items ends.
+pub(crate) fn solve_stream(input: Box<dyn Iterator<Item = String>>, part: Part, emit: &mut dyn FnMut(String)) {
+    let mut on_group = |total: usize| emit(format!("{}", total));
+
+    match part {
+        Part::Part1 => get_top_n::<{N_ELVES_PT1+1}>(input, &mut on_group),
+        Part::Part2 => get_top_n::<{N_ELVES_PT2+1}>(input, &mut on_group),
+    };
+}
+
 /// make sure we pass in `N+1` otherwise, we'll constantly overwrite the `N`th value instead of pushing it out.
-fn get_top_n<const N:usize>(mut lines: Box<dyn Iterator<Item=String>>) -> usize {
+/// `on_group` gets the running total after each (blank line terminated) group.
+fn get_top_n<const N:usize>(lines: Box<dyn Iterator<Item=String>>, on_group: &mut dyn FnMut(usize)) -> usize {
     
     let mut top_n: [usize; N] = from_fn(|_i| 0);
     let mut curr_cals: usize = 0;
 
-    while let Some(line) = lines.next() {
+    for (i, line) in lines.enumerate() {
         if line.is_empty() {
             top_n[N-1] = curr_cals;
             top_n.sort_by(|a,b| a.cmp(b).reverse());
+            on_group(top_n.iter().sum::<usize>() - top_n[N-1]);
 
             curr_cals = 0;
             continue;
         }
 
-        if let Ok(cal) = line.parse::<usize>() {
+        // anything that isn't a number is skipped, `validate` is what reports it
+        if let Ok(cal) = parse::parse_line(&line, i + 1, parse_calories) {
             curr_cals += cal
         }
     }
@@ -38,9 +53,19 @@ fn get_top_n<const N:usize>(mut lines: Box<dyn Iterator<Item=String>>) -> usize
     if curr_cals > 0 {
         top_n[N-1] = curr_cals;
         top_n.sort_by(|a,b| a.cmp(b).reverse());
+        on_group(top_n.iter().sum::<usize>() - top_n[N-1]);
     }
 
-    top_n.into_iter().fold(0, |acc, x| acc + x) - top_n[N-1]
+    top_n.into_iter().sum::<usize>() - top_n[N-1]
+}
+
+/// the calories of one item, blank lines separate each elf's items.
+fn parse_calories(c: &mut Cursor) -> ParseResult<usize> {
+    c.integer()
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    parse::validate_lines(input, |c| if c.is_at_end() { Ok(None) } else { parse_calories(c).map(Some) })
 }
 
 
@@ -64,7 +89,7 @@ fn test_input() {
 10000";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
     let output = solve(Box::new(lines.clone()), Part::Part1);
     assert_eq!(output.as_str(), "24000");
@@ -72,3 +97,33 @@ fn test_input() {
     let output = solve(Box::new(lines), Part::Part2);
     assert_eq!(output.as_str(), "45000");
 }
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["1000", "20o0", "", "4000", "-5"], &[2, 5]);
+}
+
+#[test]
+fn test_stream() {
+    const EXAMPLE: &str = r"1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let mut outputs: Vec<String> = Vec::new();
+    solve_stream(Box::new(lines), Part::Part2, &mut |s| outputs.push(s));
+    assert_eq!(outputs, vec!["6000", "10000", "21000", "41000", "45000"]);
+}
@@ -1,9 +1,12 @@
 use crate::Part;
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
 
 trait ScoreValue {
     fn val(&self) -> usize;
 }
 
+#[derive(Clone)]
 enum Move {
     Rock,
     Paper,
@@ -106,28 +109,49 @@ impl ScoreValue for Outcome {
     }
 }
 
-pub(crate) fn solve(mut input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    format!("{}", total_score(input, part, &mut |_| {}))
+}
+
+/// emits the running score after every round.
+pub(crate) fn solve_stream(input: Box<dyn Iterator<Item = String>>, part: Part, emit: &mut dyn FnMut(String)) {
+    total_score(input, part, &mut |score| emit(format!("{}", score)));
+}
+
+fn total_score(input: Box<dyn Iterator<Item = String>>, part: Part, on_round: &mut dyn FnMut(usize)) -> usize {
 
     let mut total_score:usize = 0;
 
-    while let Some(line) = input.next() {
-        if let Some((left, right)) = line.split_once(' ') {
-            let opponent_move:Move = left.into();
+    // rounds that don't parse are skipped, `validate` is what reports them
+    for (opponent_move, response) in parse::valid_lines(input, parse_round) {
             
-            total_score += match part {
-                Part::Part1 => {
-                    let our_move:Move = right.into();
-                    calc_score(&our_move, &opponent_move)
-                },
-                Part::Part2 => {
-                    let outcome:Outcome = right.into();
-                    calc_score_from(&opponent_move, &outcome)
-                }
-            };
-        }
+        total_score += match part {
+            Part::Part1 => {
+                let our_move:Move = response.into();
+                calc_score(&our_move, &opponent_move)
+            },
+            Part::Part2 => {
+                let outcome:Outcome = response.into();
+                calc_score_from(&opponent_move, &outcome)
+            }
+        };
+        on_round(total_score);
     }
 
-    format!("{}", total_score)
+    total_score
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    parse::validate_lines(input, parse_round)
+}
+
+/// `<opponent> <response>`, where the response is our move (part 1) or the outcome (part 2).
+fn parse_round(c: &mut Cursor) -> ParseResult<(Move, &'static str)> {
+    let opponent_move = c.one_of(&[("A", Move::Rock), ("B", Move::Paper), ("C", Move::Scissors)])?;
+    c.literal(" ")?;
+    let response = c.one_of(&[("X", "X"), ("Y", "Y"), ("Z", "Z")])?;
+
+    Ok((opponent_move, response))
 }
 
 fn calc_score(us: &Move, them: &Move) -> usize {
@@ -152,7 +176,7 @@ B X
 C Z";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
     let output = solve(Box::new(lines.clone()), Part::Part1);
     assert_eq!(output.as_str(), "15");
@@ -160,3 +184,19 @@ C Z";
     let output = solve(Box::new(lines), Part::Part2);
     assert_eq!(output.as_str(), "12");
 }
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["A Y", "B  X", "D Z", "C", "C Z"], &[2, 3, 4]);
+}
+
+#[test]
+fn test_stream() {
+    let lines = ["A Y", "B X", "C Z"]
+        .into_iter()
+        .map(String::from);
+
+    let mut outputs: Vec<String> = Vec::new();
+    solve_stream(Box::new(lines), Part::Part1, &mut |s| outputs.push(s));
+    assert_eq!(outputs, vec!["8", "9", "15"]);
+}
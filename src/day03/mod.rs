@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
 use crate::Part;
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
 
 const LOWERCASE_OFFSET: u8 = 96;
 const UPPERCASE_OFFSET: u8 = 38;
@@ -12,12 +14,11 @@ struct ItemFlag {
 impl ItemFlag {
     // add a byte that represents the item as a character (a-z,A-Z)
     fn add(&mut self, item: &u8) {
-        let offset:u8;
-        match item {
-            97..=122 => offset = item - LOWERCASE_OFFSET,
-            65..=90 => offset = item - UPPERCASE_OFFSET,
+        let offset:u8 = match item {
+            97..=122 => item - LOWERCASE_OFFSET,
+            65..=90 => item - UPPERCASE_OFFSET,
             _ => return,
-        }
+        };
 
         self.flag |= 1 << (offset - 1);
     }
@@ -66,11 +67,11 @@ pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> Stri
     format!("{}", total)
 }
 
-fn calc_total_priorities_pt1(mut lines: Box<dyn Iterator<Item = String>>) -> usize {
+fn calc_total_priorities_pt1(lines: Box<dyn Iterator<Item = String>>) -> usize {
 
     let mut total_score: usize = 0;
     
-    while let Some(line) = lines.next() {
+    for line in lines {
         let split_idx = line.len() / 2;
         let (left, right) = line.split_at(split_idx);
 
@@ -87,14 +88,13 @@ fn calc_total_priorities_pt1(mut lines: Box<dyn Iterator<Item = String>>) -> usi
 
     total_score
 }
-fn calc_total_priorities_pt2(mut lines: Box<dyn Iterator<Item = String>>) -> usize {
+fn calc_total_priorities_pt2(lines: Box<dyn Iterator<Item = String>>) -> usize {
 
     let mut total_score: usize = 0;
     
-    let mut idx: usize = 0;
     let mut group = (ItemFlag{flag:0}, ItemFlag{flag:0}, ItemFlag{flag:0});
 
-    while let Some(line) = lines.next() {
+    for (idx, line) in lines.enumerate() {
         let mut pack = ItemFlag { flag:0 };
         line.as_bytes().iter().for_each(|item | pack.add(item));
         
@@ -114,14 +114,35 @@ fn calc_total_priorities_pt2(mut lines: Box<dyn Iterator<Item = String>>) -> usi
             2 => group.2.flag = pack.flag,
             _ => unreachable!(),
         }
-
-        idx += 1;
     }
     total_score += calc_group_priorities(&group);
 
     total_score
 }
 
+/// a rucksack's items (a-z or A-Z), split evenly between its two compartments.
+fn parse_rucksack(c: &mut Cursor) -> ParseResult<()> {
+    let items = c.rest();
+    if items.is_empty() {
+        return Err(c.error("rucksack is empty"));
+    }
+    while !c.is_at_end() {
+        let at_item = c.clone();
+        let item = c.char()?;
+        if !item.is_ascii_alphabetic() {
+            return Err(at_item.error(format!("items should be a-z or A-Z, found '{}'", item)));
+        }
+    }
+    if !items.len().is_multiple_of(2) {
+        return Err(c.error(format!("rucksack has an odd number of items ({})", items.len())));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    parse::validate_lines(input, parse_rucksack)
+}
+
 fn calc_group_priorities(group: &(ItemFlag, ItemFlag, ItemFlag) ) -> usize {
     let result = group.0
                         .intersect(&group.1)
@@ -141,11 +162,11 @@ fn test_utf8_vals() {
 
     // print!("a:{} - z:{} | A:{} - Z:{} \n", a, z, cap_a, cap_z);
 
-    assert_eq!(a - u8::try_from(LOWERCASE_OFFSET).unwrap(), 1);
-    assert_eq!(z - u8::try_from(LOWERCASE_OFFSET).unwrap(), 26);
+    assert_eq!(a - LOWERCASE_OFFSET, 1);
+    assert_eq!(z - LOWERCASE_OFFSET, 26);
 
-    assert_eq!(cap_a - u8::try_from(UPPERCASE_OFFSET).unwrap(), 27);
-    assert_eq!(cap_z - u8::try_from(UPPERCASE_OFFSET).unwrap(), 52);
+    assert_eq!(cap_a - UPPERCASE_OFFSET, 27);
+    assert_eq!(cap_z - UPPERCASE_OFFSET, 52);
 }
 
 #[test]
@@ -190,10 +211,15 @@ ttgJtRGJQctTZtZT
 CrZsJsPPZsGzwwsLwLmpwMDw";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
     let output = solve(Box::new(lines.clone()), Part::Part1);
     assert_eq!(output.as_str(), "157");
     let output = solve(Box::new(lines.clone()), Part::Part2);
     assert_eq!(output.as_str(), "70");
 }
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["vJrwpWtwJgWrhcsFMMfFFhFp", "vJrwpWtw1gWr", "abc", ""], &[2, 3, 4]);
+}
@@ -1,55 +1,73 @@
 use crate::Part;
+use crate::interval::Interval;
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
 
-struct Range {
-    start: usize,
-    end: usize,
+/// `<start>-<end>`
+fn parse_bounds(c: &mut Cursor) -> ParseResult<(u32, u32)> {
+    let start = c.integer()?;
+    c.literal("-")?;
+    let end = c.integer()?;
+
+    Ok((start, end))
 }
 
-impl Range {
-    fn fully_overlaps(&self, other: &Range) -> bool {
-        self.start <= other.start &&
-        self.end >= other.end
-    }
-    
-    fn has_overlap(&self, other: &Range) -> bool {
-        !(self.start > other.end ||
-          other.start > self.end)  
+/// a range, with its ends in either order.
+fn parse_range(c: &mut Cursor) -> ParseResult<Interval> {
+    let (start, end) = parse_bounds(c)?;
+    Ok(Interval::new(start.into(), end.into()))
+}
+
+/// a range that doesn't end before it starts, like every range in the puzzle.
+fn parse_ordered_range(c: &mut Cursor) -> ParseResult<Interval> {
+    let at_range = c.clone();
+    let (start, end) = parse_bounds(c)?;
+    if end < start {
+        return Err(at_range.error(format!("range {}-{} ends before it starts", start, end)));
     }
+    Ok(Interval::new(start.into(), end.into()))
 }
 
-impl From<&str> for Range {
-    fn from(value: &str) -> Self {
-        if let Some((left, right)) = value.split_once('-') {
-            let start:usize = left.parse().unwrap_or(0);
-            let end:usize = right.parse().unwrap_or(0);
+/// `<range>,<range>`, `range` parses each one.
+fn parse_pair(c: &mut Cursor, range: fn(&mut Cursor) -> ParseResult<Interval>) -> ParseResult<(Interval, Interval)> {
+    let elf1 = range(c)?;
+    c.literal(",")?;
+    let elf2 = range(c)?;
 
-            Self{ start: start.min(end), end: end.max(start) }
-        } else {
-            Self{ start: 0, end: 0 }
-        }
-    }
+    Ok((elf1, elf2))
 }
 
 
-pub(crate) fn solve(mut input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    format!("{}", count_overlaps(input, part, &mut |_| {}))
+}
+
+/// emits the running count after every pair.
+pub(crate) fn solve_stream(input: Box<dyn Iterator<Item = String>>, part: Part, emit: &mut dyn FnMut(String)) {
+    count_overlaps(input, part, &mut |count| emit(format!("{}", count)));
+}
+
+fn count_overlaps(input: Box<dyn Iterator<Item = String>>, part: Part, on_pair: &mut dyn FnMut(usize)) -> usize {
     
     let mut overlap_total: usize = 0;
 
-    while let Some(line) = input.next() {
-        if let Some((left, right)) = line.split_once(',') {
-            let elf1:Range = left.into();
-            let elf2:Range = right.into();
-
-            if match part {
-              Part::Part1 => elf1.fully_overlaps(&elf2) || elf2.fully_overlaps(&elf1),
-              Part::Part2 => elf1.has_overlap(&elf2),
-            } {
-                overlap_total += 1;
-            }
+    // pairs that don't parse are skipped, `validate` is what reports them
+    for (elf1, elf2) in parse::valid_lines(input, |c| parse_pair(c, parse_range)) {
+
+        if match part {
+          Part::Part1 => elf1.contains_interval(&elf2) || elf2.contains_interval(&elf1),
+          Part::Part2 => elf1.overlaps(&elf2),
+        } {
+            overlap_total += 1;
         }
+        on_pair(overlap_total);
     }
 
-    format!("{}", overlap_total)
+    overlap_total
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    parse::validate_lines(input, |c| parse_pair(c, parse_ordered_range))
 }
 
 
@@ -64,7 +82,7 @@ fn test_input() {
 2-6,4-8";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
     let output = solve(Box::new(lines.clone()), Part::Part1);
     assert_eq!(output.as_str(), "2");
@@ -72,3 +90,29 @@ fn test_input() {
     let output = solve(Box::new(lines), Part::Part2);
     assert_eq!(output.as_str(), "4");
 }
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["2-4,6-8", "2-3;4-5", "5-7,7", "2-8,3-x", "6-6,4-6", "6-2,4-4"], &[2, 3, 4, 6]);
+}
+
+#[test]
+fn test_stream() {
+    let lines = ["2-4,6-8", "2-3,4-5", "5-7,7-9", "2-8,3-7"]
+        .into_iter()
+        .map(String::from);
+
+    let mut outputs: Vec<String> = Vec::new();
+    solve_stream(Box::new(lines), Part::Part2, &mut |s| outputs.push(s));
+    assert_eq!(outputs, vec!["0", "0", "1", "2"]);
+}
+
+#[test]
+// pairs that don't parse are skipped (`validate` reports them), rather than stopping the solve
+fn test_skips_malformed() {
+    let lines = ["2-4,6-8", "2-3;4-5", "5-7,7-9", "2-8,3-x", "6-6,4-6"]
+        .into_iter()
+        .map(String::from);
+
+    assert_eq!(solve(Box::new(lines), Part::Part2), "2");
+}
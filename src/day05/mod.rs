@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
 use crate::Part;
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
 
 type StackID = char;
 type CrateID = char;
@@ -24,7 +26,7 @@ impl From<Vec<String>> for CrateState {
             // parse columns
 
             char_idxes.for_each(|(i, c)| {
-                let _ = c.is_digit(10) && 
+                let _ = c.is_ascii_digit() && 
                 // we only want to run this if 'c' is a digit, 
                 // and this should allow this to compile as branchless
                 stack_idx_map.insert(c, i).is_none();
@@ -33,23 +35,21 @@ impl From<Vec<String>> for CrateState {
         // drop mutability
         let stack_idx_map = stack_idx_map;
 
-        let mut crate_stacks: HashMap<StackID, Vec<CrateID>> = HashMap::new();
+        let mut crate_stacks: HashMap<StackID, Vec<CrateID>> = stack_idx_map.keys()
+            .map(|id| (id.to_owned(), Vec::new()))
+            .collect();
         while let Some(line) = header_lines.pop() {
             let line_chars: Vec<char> = line.chars().collect();
             // parse the crates in each column
 
             stack_idx_map.iter().for_each(|(id,idx)| {
-                let crate_id = line_chars.get(idx.to_owned())
-                    .expect("'idx' must map to a value in the preceding lines")
-                    .to_owned();
+                // lines may have been right-trimmed, so a missing column is just an empty spot.
+                let Some(crate_id) = line_chars.get(idx.to_owned()) else { return; };
 
                 if crate_id.is_whitespace() { return; }
 
-                if !crate_stacks.contains_key(id) {
-                    crate_stacks.insert(id.to_owned(), Vec::new());
-                }
-                let stack = crate_stacks.get_mut(id).expect("already checked");
-                stack.push(crate_id);
+                let stack = crate_stacks.get_mut(id).expect("every stack id was added above");
+                stack.push(crate_id.to_owned());
             });
         }
 
@@ -61,8 +61,8 @@ impl From<Vec<String>> for CrateState {
 impl CrateState {
     fn apply(&mut self, cmd: Command) {
         if cmd.from == cmd.to ||
-            self.crate_stacks.get(&cmd.from).is_none() || 
-            self.crate_stacks.get(&cmd.to).is_none()  {
+            !self.crate_stacks.contains_key(&cmd.from) || 
+            !self.crate_stacks.contains_key(&cmd.to)  {
             return;
         }
 
@@ -117,36 +117,44 @@ struct Command {
 }
 
 impl Command {
-    fn as_9000(mut self) -> Self {
+    fn into_9000(mut self) -> Self {
         self.version = CraneType::CrateMover9000;
         self
     }
     
-    fn as_9001(mut self) -> Self {
+    fn into_9001(mut self) -> Self {
         self.version = CraneType::CrateMover9001;
         self
     }
 }
 
-impl From<String> for Command {
-    fn from(line: String) -> Self {
-        let mut num: usize = 0;
-        let mut from: StackID = '0';
-        let mut to: StackID = '0';
-
-        let mut components = line.split(' ');
-        for _ in 0..3 {
-            if let (Some(op), Some(val)) = (components.next(), components.next()) {
-                match (op, val) {
-                    ("move", num_str) => num = num_str.parse().unwrap_or_default(),
-                    ("from", from_str) => from = from_str.chars().next().unwrap_or('0'),
-                    ("to", to_str) => to = to_str.chars().next().unwrap_or('0'),
-                    _ => unimplemented!("unknown command syntax"),
-                }
-            }
+impl Command {
+    /// `move <n> from <stack> to <stack>`
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        c.literal("move ")?;
+        let num = c.integer()?;
+        c.literal(" from ")?;
+        let from = c.char()?;
+        c.literal(" to ")?;
+        let to = c.char()?;
+
+        Ok(Command { num, from, to, version: CraneType::CrateMover9000 })
+    }
+
+    /// like `parse`, but a command whose values don't parse (ie: `move two from 2 to 1`) moves nothing,
+    /// as it always has. only a line that isn't a `move ... from ... to ...` at all is an error.
+    fn parse_lenient(c: &mut Cursor) -> ParseResult<Option<Self>> {
+        if let Some(cmd) = c.attempt(Command::parse) {
+            return Ok(Some(cmd));
         }
 
-        return Command { num, from, to, version: CraneType::CrateMover9000 }
+        c.literal("move ")?;
+        c.word()?;
+        c.literal(" from ")?;
+        c.word()?;
+        c.literal(" to ")?;
+        c.word()?;
+        Ok(None)
     }
 }
 
@@ -156,22 +164,27 @@ pub(crate) fn solve(mut input: Box<dyn Iterator<Item = String>>, part: Part) ->
     
     // step 1: parse header to get initial state of crate stacks:
     let mut header_lines: Vec<String> = Vec::new();
-    while let Some(line) = input.next() {
+    for line in input.by_ref() {
         if line.is_empty() {
             break;
         }
         header_lines.push(line);
     }
 
+    // the commands start after the header & the blank line below it
+    let first_command_line = header_lines.len() + 2;
     let mut crates: CrateState = header_lines.into();
 
     // step 2: parse & apply each 'command'
-    while let Some(line) = input.next() {
-        let cmd: Command = line.into();
+    // `validate` reports the commands that are skipped
+    for cmd in parse::lines_from(input, first_command_line, Command::parse_lenient) {
+        let Some(cmd) = cmd.unwrap_or_else(|e| panic!("{}", e)) else {
+            continue;
+        };
 
         match part {
-            Part::Part1 => crates.apply(cmd.as_9000()),
-            Part::Part2 => crates.apply(cmd.as_9001()),
+            Part::Part1 => crates.apply(cmd.into_9000()),
+            Part::Part2 => crates.apply(cmd.into_9001()),
         }
     }
 
@@ -180,6 +193,81 @@ pub(crate) fn solve(mut input: Box<dyn Iterator<Item = String>>, part: Part) ->
 }
 
 
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut lines = input.enumerate().map(|(i, line)| (i + 1, line));
+
+    // step 1: collect the header, it can only be checked once we've seen the stack ids.
+    let mut header_lines: Vec<(usize, String)> = Vec::new();
+    let mut found_separator = false;
+    for (line_no, line) in lines.by_ref() {
+        if line.is_empty() {
+            found_separator = true;
+            break;
+        }
+        header_lines.push((line_no, line));
+    }
+
+    let Some((ids_line_no, ids_line)) = header_lines.pop() else {
+        issues.push(Issue::new(1, "missing the crate stack header"));
+        return issues;
+    };
+
+    let mut stack_ids: HashMap<usize, StackID> = HashMap::new();
+    for (i, c) in ids_line.char_indices() {
+        if c.is_whitespace() { continue; }
+
+        if !c.is_ascii_digit() {
+            issues.push(Issue::new(ids_line_no, format!("stack ids should be single digits, found '{}' at column {}", c, i + 1)));
+        } else if stack_ids.values().any(|id| *id == c) {
+            issues.push(Issue::new(ids_line_no, format!("stack id '{}' is used more than once", c)));
+        } else {
+            stack_ids.insert(i, c);
+        }
+    }
+
+    if stack_ids.is_empty() {
+        issues.push(Issue::new(ids_line_no, "expected a row of stack ids below the crates"));
+    }
+
+    for (line_no, line) in header_lines {
+        let chars: Vec<char> = line.chars().collect();
+        for (i, c) in chars.iter().enumerate() {
+            if c.is_whitespace() { continue; }
+
+            let lines_up = match c {
+                '[' => stack_ids.contains_key(&(i + 1)) && chars.get(i + 2) == Some(&']'),
+                ']' => i > 0 && stack_ids.contains_key(&(i - 1)) && chars.get(i - 2) == Some(&'['),
+                _ => stack_ids.contains_key(&i) && c.is_ascii_alphabetic(),
+            };
+
+            if !lines_up {
+                issues.push(Issue::new(line_no, format!("'{}' at column {} doesn't line up with a stack column", c, i + 1)));
+                break;
+            }
+        }
+    }
+
+    if !found_separator {
+        issues.push(Issue::new(ids_line_no + 1, "expected a blank line between the header and the commands"));
+        return issues;
+    }
+
+    // step 2: every remaining line is a command
+    for (line_no, line) in lines {
+        let issue = match parse::parse_line(&line, line_no, Command::parse) {
+            Ok(cmd) => [cmd.from, cmd.to].into_iter()
+                .find(|id| !stack_ids.values().any(|s| s == id))
+                .map(|id| Issue::new(line_no, format!("unknown stack '{}'", id))),
+            Err(e) => Some(e.into()),
+        };
+
+        issues.extend(issue);
+    }
+
+    issues
+}
+
 
 #[test]
 // sanity check vs example input
@@ -195,7 +283,7 @@ move 2 from 2 to 1
 move 1 from 1 to 2";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
 
     let output = solve(Box::new(lines.clone()), Part::Part1);
@@ -203,4 +291,79 @@ move 1 from 1 to 2";
     
     let output = solve(Box::new(lines), Part::Part2);
     assert_eq!(output.as_str(), "MCD");
-}
\ No newline at end of file
+}
+
+#[test]
+// the header rows are only padded with spaces, so they shouldn't need to be.
+fn test_input_trimmed_header() {
+    const EXAMPLE: &str = r"    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "CMZ");
+
+    let output = solve(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "MCD");
+}
+
+#[test]
+fn test_validate() {
+    const EXAMPLE: &str = r"    [D]    
+[N] [C]    
+ [Z][M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 4
+move two from 2 to 1
+shift 1 from 1 to 2";
+
+    let lines: Vec<&str> = EXAMPLE.split('\n').collect();
+
+    crate::validate::assert_issue_lines(validate, &lines, &[3, 7, 8, 9]);
+}
+#[test]
+#[should_panic(expected = "line 7, column 1: expected 'move ', found 'shift 1 from 2 to 1'")]
+// errors point at the line in the whole input, not just in the commands below the header
+fn test_command_error_line() {
+    const EXAMPLE: &str = r"    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+shift 1 from 2 to 1";
+
+    solve(Box::new(EXAMPLE.split('\n').map(String::from)), Part::Part1);
+}
+
+#[test]
+// a command with values that don't parse moves nothing
+fn test_skips_bad_values() {
+    const EXAMPLE: &str = r"    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move two from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from x to 2
+move 1 from 1 to 2";
+
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    assert_eq!(solve(Box::new(lines), Part::Part1), "CMZ");
+}
@@ -1,12 +1,16 @@
 use std::fmt::Display;
 use std::collections::VecDeque;
+use std::io::{BufRead, Read};
 
 use crate::Part;
+use crate::input;
+use crate::validate::{Issue, check_lines};
 
 const LOWERCASE_OFFSET: u8 = 96;
 const UPPERCASE_OFFSET: u8 = 38;
 const DUP_MASK:u64 = 1 << 63; 
 
+#[derive(Default)]
 struct CharFlags {
     pub flag: u64,
 }
@@ -15,12 +19,11 @@ impl CharFlags {
 
     // add a byte that represents the item as a character (a-z,A-Z)
     fn add(&mut self, item: &u8) -> Self {
-        let offset:u8;
-        match item {
-            97..=122 => offset = item - LOWERCASE_OFFSET,
-            65..=90 => offset = item - UPPERCASE_OFFSET,
+        let offset:u8 = match item {
+            97..=122 => item - LOWERCASE_OFFSET,
+            65..=90 => item - UPPERCASE_OFFSET,
             _ => { return CharFlags{ flag: self.flag.to_owned() }; },
-        }
+        };
 
         let other_flag:u64 = 1 << (offset - 1);
         let is_dupe:bool = (self.flag & other_flag) != 0;
@@ -48,11 +51,6 @@ impl From<&VecDeque<u8>> for CharFlags {
     }
 }
 
-impl Default for CharFlags {
-    fn default() -> Self {
-        CharFlags { flag: 0 }
-    }
-}
 
 impl Display for CharFlags {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -65,50 +63,112 @@ impl Display for CharFlags {
 }
 
 
-pub(crate) fn solve(mut input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
     
     let mut idxes:Vec<usize> = Vec::new();
 
-    let packet_len: usize = match part {
+    find_markers(input, part, &mut |idx| idxes.push(idx));
+
+    format!("{:?}", idxes)
+        .replace(",", "")
+        .trim_matches(|c| c == '[' || c == ']')
+        .to_string()
+}
+
+/// emits each marker as soon as it's read, without waiting for the rest of its (usually very long) datastream.
+pub(crate) fn solve_stream(mut input: Box<dyn BufRead>, part: Part, emit: &mut dyn FnMut(String)) {
+    input::skip_bom(input.as_mut());
+
+    let mut finder = MarkerFinder::new(packet_len(&part));
+    // whitespace read since the last non-whitespace byte. `input::normalize` trims it from the end of each line
+    // (a `\r` included), so it's only part of the datastream once something follows it on the same line.
+    let mut pending: Vec<u8> = Vec::new();
+    for byte in Read::bytes(input).map_while(Result::ok) {
+        match byte {
+            // each line is its own datastream
+            b'\n' => {
+                finder = MarkerFinder::new(finder.len);
+                pending.clear();
+            },
+            _ if byte.is_ascii_whitespace() => pending.push(byte),
+            _ => for byte in pending.drain(..).chain([byte]) {
+                if let Some(idx) = finder.push(byte) {
+                    emit(format!("{}", idx));
+                }
+            },
+        }
+    }
+}
+
+fn packet_len(part: &Part) -> usize {
+    match part {
         Part::Part1 => 4,
         Part::Part2 => 14,
-    };
+    }
+}
+
+fn find_markers(input: Box<dyn Iterator<Item = String>>, part: Part, on_marker: &mut dyn FnMut(usize)) {
+    let packet_len = packet_len(&part);
 
-    while let Some(line) = input.next() {
+    for line in input {
         if let Some(idx) = find_start(&line, packet_len) {
-            idxes.push(idx);
+            on_marker(idx);
         }
     }
+}
 
-    format!("{:?}", idxes)
-        .replace(",", "")
-        .trim_matches(|c| c == '[' || c == ']')
-        .to_string()
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    check_lines(input, |line| {
+        if line.is_empty() {
+            return Some("datastream is empty".into());
+        }
+        line.char_indices()
+            .find(|(_, c)| !c.is_ascii_lowercase())
+            .map(|(i, c)| format!("datastream should only contain a-z, found '{}' at column {}", c, i + 1))
+    })
 }
 
-fn find_start(line: &String, len: usize) -> Option<usize> {
+fn find_start(line: &str, len: usize) -> Option<usize> {
+    let mut finder = MarkerFinder::new(len);
 
-    let mut chars = line.char_indices();
-    let mut buf: VecDeque<u8> = VecDeque::new();
+    // our input is constrained to lowercase ascii characters, so each byte is a char
+    line.bytes().find_map(|byte| finder.push(byte))
+}
 
-    while let Some((i, c)) = chars.next() {
-        let mut byte: [u8; 1] = [0];
-        // SAFETY: could panic on unexpected values
-        //         but our input is constrained to lowercase ascii characters
-        c.encode_utf8(&mut byte);
-        buf.push_back(byte.first().unwrap().to_owned());
+/// watches a datastream a byte at a time, for the first `len` bytes in a row that are all different.
+struct MarkerFinder {
+    len: usize,
+    buf: VecDeque<u8>,
+    read: usize,
+    found: bool,
+}
 
-        if buf.len() == len {
-            let flags: CharFlags = (&buf).into();
+impl MarkerFinder {
+    fn new(len: usize) -> Self {
+        Self { len, buf: VecDeque::with_capacity(len), read: 0, found: false }
+    }
+
+    /// returns the marker's position if `byte` completes it (only the first marker is reported).
+    fn push(&mut self, byte: u8) -> Option<usize> {
+        if self.found {
+            return None;
+        }
+
+        self.read += 1;
+        self.buf.push_back(byte);
+
+        if self.buf.len() == self.len {
+            let flags: CharFlags = (&self.buf).into();
             if !flags.has_dupes() {
-                return Some(i + 1);
+                self.found = true;
+                return Some(self.read);
             }
 
-            let _ = buf.pop_front(); // discard front
+            let _ = self.buf.pop_front(); // discard front
         }
-    }
 
-    None
+        None
+    }
 }
 
 
@@ -123,11 +183,58 @@ nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg
 zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
     let output = solve(Box::new(lines.clone()), Part::Part1);
     assert_eq!(output.as_str(), "7 5 6 10 11");
     
     let output = solve(Box::new(lines), Part::Part2);
     assert_eq!(output.as_str(), "19 23 23 29 26");
+}
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["mjqjpqmgbljsphdztnvjfqwrcgsmlb", "bvwbjpl bgvbh", "nppdvJthql"], &[2, 3]);
+}
+
+#[test]
+fn test_stream() {
+    let input = "\u{feff}mjqjpqmgbljsphdztnvjfqwrcgsmlb\r\nbvwbjplbgvbhsrlpgdmjqwftvncz\r\n";
+
+    let mut outputs: Vec<String> = Vec::new();
+    solve_stream(Box::new(input.as_bytes()), Part::Part1, &mut |s| outputs.push(s));
+    assert_eq!(outputs, vec!["7", "5"]);
+}
+
+#[test]
+// spaces inside a line are part of the datastream either way, only the line endings & trailing whitespace aren't
+fn test_stream_matches_lines() {
+    let input = "mj qjpqmgbljsph\r\nab cdd  \r\n\tbvwbjplbgvbhsrlpg \n";
+
+    let mut streamed: Vec<String> = Vec::new();
+    solve_stream(Box::new(input.as_bytes()), Part::Part1, &mut |s| streamed.push(s));
+
+    let lines = input::normalize(Box::new(input.lines().map(String::from)));
+    assert_eq!(streamed.join(" "), solve(lines, Part::Part1));
+}
+
+#[test]
+// the marker should be emitted as soon as it's read, not once the line (or input) ends.
+fn test_stream_emits_before_eof() {
+    use std::{cell::RefCell, rc::Rc};
+
+    /// fails the test if it's read from before a marker has been emitted.
+    struct Rest(Rc<RefCell<Vec<String>>>);
+    impl Read for Rest {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            assert_eq!(*self.0.borrow(), vec!["7"], "the marker should be out before reading on");
+            Ok(0)
+        }
+    }
+
+    let outputs = Rc::new(RefCell::new(Vec::new()));
+    let input = "mjqjpqmgblj".as_bytes().chain(Rest(outputs.clone()));
+
+    solve_stream(Box::new(std::io::BufReader::new(input)), Part::Part1, &mut |s| outputs.borrow_mut().push(s));
+    assert_eq!(*outputs.borrow(), vec!["7"]);
 }
\ No newline at end of file
@@ -5,9 +5,37 @@ use std::{
 };
 
 use crate::Part;
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
 
 type SharedFilePtr = Rc<File>;
 
+/// A single line of the terminal transcript.
+enum TranscriptLine {
+    Cd(String),
+    Ls,
+    /// `ls` output: `dir <name>`
+    Dir(String),
+    /// `ls` output: `<size> <name>`
+    File(usize, String),
+}
+
+impl TranscriptLine {
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        if c.try_literal("$ ") {
+            let is_cd = c.one_of(&[("cd ", true), ("ls", false)])?;
+            return Ok(if is_cd { Self::Cd(c.word()?.into()) } else { Self::Ls });
+        }
+        if c.try_literal("dir ") {
+            return Ok(Self::Dir(c.word()?.into()));
+        }
+
+        let size = c.integer()?;
+        c.literal(" ")?;
+        Ok(Self::File(size, c.word()?.into()))
+    }
+}
+
 enum File {
     File{
         name: String,
@@ -23,26 +51,19 @@ enum File {
 impl File {
     
     fn add_file(&self, new_file: File) {
-        match self {
-            File::Directory { parent: _, name: _, files } => {
-                let dir_name = { new_file.get_name() };
+        if let File::Directory { parent: _, name: _, files } = self {
+            let dir_name = { new_file.get_name() };
 
-                if let Ok(mut files) = files.try_borrow_mut() {
-                    files.insert(dir_name, Rc::new(new_file));
-                }
-            },
-            _ => { return; },
-        };
+            if let Ok(mut files) = files.try_borrow_mut() {
+                files.insert(dir_name, Rc::new(new_file));
+            }
+        }
     }
 
     fn get_dir(&self, name: &String) -> Option<SharedFilePtr> {
         match self {
             File::Directory { parent: _, name: _, files } => {
-                if let Some(dir) = files.borrow().get(name) {
-                    Some(dir.clone())
-                } else {
-                    None
-                }
+                files.borrow().get(name).cloned()
             },
             _ => { None },
         }
@@ -51,11 +72,7 @@ impl File {
     fn get_parent(&self) -> Option<SharedFilePtr> {
         match self {
             File::Directory { parent, name: _, files: _ } => {
-                if let Some(parent) = parent {
-                    Some(parent.clone())
-                } else {
-                    None
-                }
+                parent.as_ref().map(|parent| parent.clone())
             },
             File::File { name: _, size: _ } => None,
         }
@@ -124,7 +141,7 @@ pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> Stri
 }
 
 
-fn parse_filesys_from(mut lines: Box<dyn Iterator<Item=String>>) -> Rc<File> {
+fn parse_filesys_from(lines: Box<dyn Iterator<Item=String>>) -> Rc<File> {
 
     let root = Rc::new(
         File::Directory { parent: None, name: "/".into(), files: RefCell::new(HashMap::new()) }
@@ -132,75 +149,67 @@ fn parse_filesys_from(mut lines: Box<dyn Iterator<Item=String>>) -> Rc<File> {
     let mut cur_dir = root.clone();
 
     // parse the input
-    while let Some(line) = lines.next() {
-        
-        // handle `cd`
-        if let Some(dir) = line.strip_prefix("$ cd ") {
-            match dir {
+    // lines that don't parse are skipped, `validate` is what reports them
+    for line in parse::valid_lines(lines, TranscriptLine::parse) {
+        match line {
+            TranscriptLine::Cd(dir) => match dir.as_str() {
                 "/" => { cur_dir = root.clone() },
                 ".." => {
-                    let new_dir: Option<SharedFilePtr> = if let Some(parent) = cur_dir.get_parent() {
-                        Some(parent)
-                    } else {
-                        None
-                    };
+                    let new_dir: Option<SharedFilePtr> = cur_dir.get_parent();
                     
-                    if new_dir.is_some() {
-                        cur_dir = new_dir.unwrap();
+                    if let Some(new_dir) = new_dir {
+                        cur_dir = new_dir;
                     }
                 },
                 _ => { 
-                    let dir_name = String::from(dir);
-                    let new_dir: Option<SharedFilePtr> = if let Some(child) = cur_dir.get_dir(&dir_name) {
-                        Some(child)
-                    } else {
-                        None
-                    };
-
-                    if new_dir.is_some() {
-                        cur_dir = new_dir.unwrap();
+                    let new_dir: Option<SharedFilePtr> = cur_dir.get_dir(&dir);
+
+                    if let Some(new_dir) = new_dir {
+                        cur_dir = new_dir;
                     }
                 },
-            }
-            continue;
-        }
-
-        // handle `ls`
-        if let Some(_) = line.strip_prefix("$ ls") {
-            continue;
+            },
+            TranscriptLine::Ls => {},
+            TranscriptLine::Dir(name) => {
+                cur_dir.add_file(File::Directory { parent: Some(cur_dir.clone()), name, files: RefCell::new(HashMap::new()) });
+            },
+            TranscriptLine::File(size, name) => cur_dir.add_file(File::File { name, size }),
         }
+    }
+    drop(cur_dir);
 
-        // handle parsing `ls` output
-        if let Some((left, right)) = line.split_once(' ') {
-            let file =  match left.parse::<usize>() {
-                Ok(size) => File::File { name: right.into(), size },
-                Err(_) => File::Directory { parent: Some(cur_dir.clone()), name: right.into(), files: RefCell::new(HashMap::new()) },
-            };
-
-            cur_dir.add_file(file);
+    root
+}
 
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut in_listing = false;
+
+    for (i, line) in input.enumerate() {
+        match parse::parse_line(&line, i + 1, TranscriptLine::parse) {
+            Ok(TranscriptLine::Cd(_)) => in_listing = false,
+            Ok(TranscriptLine::Ls) => in_listing = true,
+            Ok(_) if !in_listing => issues.push(Issue::new(i + 1, "`ls` output without a preceding `$ ls`")),
+            Ok(_) => {},
+            Err(e) => issues.push(e.into()),
         }
     }
-    drop(cur_dir);
 
-    root
+    issues
 }
 
 fn sum_folders_with_max_size_in(f: &Rc<File>, threshold: usize) -> usize {
     let mut sum = 0_usize;
-    match **f {
-        File::Directory { parent:_, name:_, ref files } => {
-            if f.size() <= threshold {
-                sum += f.size();
-            }
+    if let File::Directory { parent:_, name:_, ref files } = **f {
+        if f.size() <= threshold {
+            sum += f.size();
+        }
 
-            let files_ref = files.borrow();
-            let mut file_list = files_ref.values();
-            while let Some(file) = file_list.next() {
-                sum += sum_folders_with_max_size_in(file, threshold);
-            };
-        },
-        _=> (),
+        let files_ref = files.borrow();
+        let file_list = files_ref.values();
+        for file in file_list {
+            sum += sum_folders_with_max_size_in(file, threshold);
+        };
     };
 
     sum
@@ -218,8 +227,8 @@ fn min_folder_size_above_threshold_in(f: &Rc<File>, threshold: usize, mut cur_mi
             }
 
             let files_ref = files.borrow();
-            let mut file_list = files_ref.values();
-            while let Some(file) = file_list.next() {
+            let file_list = files_ref.values();
+            for file in file_list {
                 let c_min =  min_folder_size_above_threshold_in(file, threshold, cur_min);
                 if c_min <= cur_min {
                     cur_min = c_min;
@@ -260,7 +269,7 @@ $ ls
 7214296 k";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
     let output = solve(Box::new(lines.clone()), Part::Part1);
     assert_eq!(output.as_str(), "95437");
@@ -268,3 +277,21 @@ $ ls
     let output = solve(Box::new(lines), Part::Part2);
     assert_eq!(output.as_str(), "24933642");
 }
+
+#[test]
+fn test_validate() {
+    const EXAMPLE: &str = r"$ cd /
+$ ls
+dir a
+14848514 b.txt
+$ cd a
+123 f
+$ dir
+$ ls
+2557 g h
+xyz i";
+
+    let lines: Vec<&str> = EXAMPLE.split('\n').collect();
+
+    crate::validate::assert_issue_lines(validate, &lines, &[6, 7, 9, 10]);
+}
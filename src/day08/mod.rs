@@ -1,176 +1,87 @@
 
 use crate::Part;
+use crate::grid::{self, Coord, Direction, Grid};
+use crate::validate::{Issue, check_lines};
 
 
 pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
     
-    let forrest: Vec<Vec<i8>> = 
-        input.map(|s| s.chars()
-                .filter_map(|c| c.to_digit(10))
-                .filter_map(|d| i8::try_from(d).ok())
-                .collect())
-            .collect();
+    let forrest = Grid::parse(input, |c| c.to_digit(10).map(|d| d as i8))
+        .expect("input should be a rectangle of tree heights");
 
     let total = match part {
-        Part::Part1 => count_visible_trees(forrest),
-        Part::Part2 => calc_max_scenic_value(forrest),
+        Part::Part1 => count_visible_trees(&forrest),
+        Part::Part2 => calc_max_scenic_value(&forrest),
     };
     format!("{}", total)
 }
 
-// part 1 calculations
-fn count_visible_trees(forrest: Vec<Vec<i8>>) -> usize {
-    
-    let height = forrest.len();
-    if height < 1 { return 0; }
-    let width = forrest.first().unwrap().len();
-
-    let mut visable_tree_flags: Vec<Vec<i8>> = vec![vec![0_i8; width]; height];
-    let mut max_col_heights = vec![-1_i8; width];
-
-    // walk the rows from top->bottom & left->right
-    let mut rows = forrest.iter().enumerate();
-    while let Some((i, row)) = rows.next() {
-        let mut tree_heights = row.iter().enumerate();
-        let mut max_row_height = -1_i8;
-
-        while let Some((j, tree_height)) = tree_heights.next() {
-            if tree_height > &max_row_height {
-                visable_tree_flags[i][j] = 1;
-                max_row_height = tree_height.clone();
-            }
-            
-            if tree_height > &max_col_heights[j] {
-                visable_tree_flags[i][j] = 1;
-                max_col_heights[j] = tree_height.clone();
-            }
-        }
-    }
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut width: Option<usize> = None;
 
-    // reset max column hights
-    let mut max_col_heights = vec![-1_i8; width];
-
-    // reverse the visible flags lists:
-    let mut visable_tree_flags: Vec<Vec<i8>> = 
-        visable_tree_flags.iter().rev()
-            .map(|row| 
-                row.iter().rev().cloned().collect())
-            .collect();
-
-    // walk the rows from bottom->top & right->left
-    let mut rows = forrest.iter().rev().enumerate();
-    while let Some((i, row)) = rows.next() {
-        let mut tree_heights = row.iter().rev().enumerate();
-        let mut max_row_height = -1_i8;
-
-        while let Some((j, tree_height)) = tree_heights.next() {
-            if tree_height > &max_row_height {
-                visable_tree_flags[i][j] = 1;
-                max_row_height = tree_height.clone();
-            }
-            
-            if tree_height > &max_col_heights[j] {
-                visable_tree_flags[i][j] = 1;
-                max_col_heights[j] = tree_height.clone();
-            }
+    check_lines(Box::new(grid::grid_lines(input).into_iter()), |line| {
+        if let Some((i, c)) = line.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+            return Some(format!("tree heights should be digits, found '{}' at column {}", c, i + 1));
         }
-    }
 
-
-    visable_tree_flags.iter().fold(0_usize, |acc_total, row| {
-        acc_total +
-        row.iter().fold(0_usize, |acc_row, v| {
-            acc_row + v.to_owned().try_into().unwrap_or(0) 
-        })
+        match width {
+            None if line.is_empty() => Some("row is empty".into()),
+            None => { width = Some(line.len()); None },
+            Some(w) if w != line.len() => Some(format!("row is {} trees wide, expected {}", line.len(), w)),
+            Some(_) => None,
+        }
     })
 }
 
-// part 2 calculations
-fn calc_max_scenic_value(forrest: Vec<Vec<i8>>) -> usize {
+// part 1 calculations
+fn count_visible_trees(forrest: &Grid<i8>) -> usize {
     
-    let height = forrest.len();
-    if height < 1 { return 0; }
-    let width = forrest.first().unwrap().len();
-
-    let mut tree_scenic_values: Vec<Vec<usize>> = vec![vec![1_usize; width]; height];
-
-    for i in 0..height {
-        for j in 0..width {
-            tree_scenic_values[i][j] = 
-                calc_scenic_value_for((i,j), &forrest);
+    let mut visable_tree_flags = forrest.map(|_| false);
+
+    // look in from every tree on the edge, starting just outside the grid
+    let (width, height) = (forrest.width() as isize, forrest.height() as isize);
+    let lines_of_sight = (0..height)
+        .flat_map(|y| [(Coord::new(-1, y), Direction::Right), (Coord::new(width, y), Direction::Left)])
+        .chain((0..width)
+            .flat_map(|x| [(Coord::new(x, -1), Direction::Down), (Coord::new(x, height), Direction::Up)]));
+
+    for (edge, direction) in lines_of_sight {
+        let mut max_height = -1_i8;
+        for (loc, tree_height) in forrest.ray(edge, direction) {
+            if *tree_height > max_height {
+                visable_tree_flags[loc] = true;
+                max_height = *tree_height;
+            }
         }
     }
 
-    tree_scenic_values.iter()
-        .map(|row| row.iter().max().cloned().unwrap_or(0))
-        .max().unwrap_or(0)
+    visable_tree_flags.iter().filter(|(_, visible)| **visible).count()
 }
 
-fn calc_scenic_value_for((i,j): (usize, usize), forrest: &Vec<Vec<i8>>) -> usize {
-    
-    let height = forrest.len();
-    if height < 1 { return 1; }
-    let width = forrest.first().unwrap().len();
+// part 2 calculations
+fn calc_max_scenic_value(forrest: &Grid<i8>) -> usize {
     
-    if i > height || 
-        j > width {
-        return 1;
-    }
-
-    let our_tree_height = forrest.get(i).unwrap().get(j).unwrap();
+    forrest.coords()
+        .map(|loc| calc_scenic_value_for(loc, forrest))
+        .max().unwrap_or(0)
+}
 
-    let our_row = forrest.get(i).unwrap().clone();
-    let row_slice = our_row.as_slice();
-    let row_start = &row_slice[..j];
-    let row_end = &row_slice[j..];
-    
-    let our_col: Vec<i8> = forrest.iter().map(|row| row.get(j).unwrap().clone()).collect();
-    let col_slice = our_col.as_slice();
-    let col_start = &col_slice[..i];
-    let col_end = &col_slice[i..];
+fn calc_scenic_value_for(loc: Coord, forrest: &Grid<i8>) -> usize {
     
-    // row view distances:
-    let mut left_view_dist = 0_usize;
-    let left = row_start.iter().rev();
-    for v in left {
-        left_view_dist += 1;
-        if v >= our_tree_height {
-            break;
-        }
-    }
-
-    let mut right_view_dist = 0_usize;
-    let mut right = row_end.iter();
-    let _ = right.next();
-    for v in right {
-        right_view_dist += 1;
-        if v >= our_tree_height {
-            break;
-        }
-    }
-
-
-    // col view distance:
-    let mut up_view_dist = 0_usize;
-    let up = col_start.iter().rev();
-    for v in up {
-        up_view_dist += 1;
-        if v >= our_tree_height {
-            break;
-        }
-    }
-
-    let mut down_view_dist = 0_usize;
-    let mut down = col_end.iter();
-    let _ = down.next();
-    for v in down {
-        down_view_dist += 1;
-        if v >= our_tree_height {
-            break;
-        }
-    }
-
-    up_view_dist * left_view_dist * down_view_dist * right_view_dist
+    let our_tree_height = forrest[loc];
+
+    Direction::ALL.into_iter()
+        .map(|direction| {
+            let mut view_dist = 0_usize;
+            for (_, v) in forrest.ray(loc, direction) {
+                view_dist += 1;
+                if *v >= our_tree_height {
+                    break;
+                }
+            }
+            view_dist
+        })
+        .product()
 }
 
 
@@ -184,7 +95,7 @@ fn test_input() {
 35390";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
     let output = solve(Box::new(lines.clone()), Part::Part1);
     assert_eq!(output.as_str(), "21");
@@ -193,6 +104,13 @@ fn test_input() {
     assert_eq!(output.as_str(), "8");
 }
 
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["30373", "2551", "65332", "33a49", "353900"], &[2, 4, 5]);
+    // like `solve`, blank lines at the end are fine
+    crate::validate::assert_issue_lines(validate, &["303", "255", "", ""], &[]);
+}
+
 
 #[cfg(test_output)]
 #[test]
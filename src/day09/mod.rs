@@ -2,84 +2,62 @@
 use std::{collections::HashSet, rc::Rc, cell::RefCell};
 
 use crate::Part;
+use crate::grid::{Coord, Direction};
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
 
 
 trait Move {
     fn apply_movement(&mut self, direction: &Movement);
 }
 
-enum Movement {
-    Up(i32),
-    Down(i32),
-    Left(i32),
-    Right(i32),
+struct Movement {
+    direction: Direction,
+    dist: isize,
 }
 
-impl From<&str> for Movement {
-    fn from(value: &str) -> Self {
-        let (left, right) = value.split_once(' ')
-            .expect("input should be in the form of '<direction> <moves>'");
-
-        let dist: i32 = right.parse()
-            .expect("<moves> should be a number");
-
-        match left {
-            "U" => Movement::Up(dist),
-            "D" => Movement::Down(dist),
-            "L" => Movement::Left(dist),
-            "R" => Movement::Right(dist),
-            _ => Movement::Up(0),
-        }
+impl Movement {
+    /// `<direction> <moves>`, ie: `R 4`
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        let direction = c.one_of(&[
+            ("U", Direction::Up),
+            ("D", Direction::Down),
+            ("L", Direction::Left),
+            ("R", Direction::Right),
+        ])?;
+        c.literal(" ")?;
+        let dist = c.integer::<u32>()? as isize;
+
+        Ok(Self { direction, dist })
     }
-}
-
-
-#[derive(Default, Clone, PartialEq, Eq, Hash)]
-struct Loc {
-    x: i32,
-    y: i32,
-}
 
-impl Move for Loc {
-    fn apply_movement(&mut self, direction: &Movement) {
-        let (dx, dy):(i32,i32) = match direction {
-            Movement::Up(dist) => (0, dist.clone()),
-            Movement::Down(dist) => (0, dist * -1),
-            Movement::Left(dist) => (dist * -1, 0),
-            Movement::Right(dist) => (dist.clone(), 0),
-        };
+    /// like `parse`, but a movement in an unknown direction (ie: `X 4`) doesn't move the rope, as it always has.
+    /// only a line that isn't `<direction> <moves>` at all is an error.
+    fn parse_lenient(c: &mut Cursor) -> ParseResult<Option<Self>> {
+        if let Some(movement) = c.attempt(Movement::parse) {
+            return Ok(Some(movement));
+        }
 
-        self.x += dx;
-        self.y += dy;
+        c.word()?;
+        c.literal(" ")?;
+        c.integer::<i32>()?;
+        Ok(None)
     }
 }
 
-impl Loc {
-    fn step_towards(&mut self, other: &Loc) {
-        let (mut dx, mut dy) = (other.x - self.x, other.y - self.y);
-        
-        if dx > 0 { dx = 1 }
-        if dx < 0 { dx = -1 }
-        if dy > 0 { dy = 1 }
-        if dy < 0 { dy = -1 }
 
-        self.x += dx;
-        self.y += dy;
-    }
-
-    fn square_dist(&self, other: &Loc) -> i32 {
-        let (dx, dy) = (other.x.abs_diff(self.x) as i32, other.y.abs_diff(self.y) as i32);
-
-        // this assumes 1 diagonal unit == 1 unit
-        // (ie: [2,2] only takes 2 moves to traverse)
-        (dx + dy) - (dx.min(dy))
+impl Move for Coord {
+    fn apply_movement(&mut self, movement: &Movement) {
+        let delta = movement.direction.delta();
+        self.x += delta.x * movement.dist;
+        self.y += delta.y * movement.dist;
     }
 }
 
 
 struct Snake {
     body: Rc<RefCell<SnakeSegment>>,
-    tail_trail: HashSet<Loc>,
+    tail_trail: HashSet<Coord>,
 }
 
 impl Snake {
@@ -90,7 +68,7 @@ impl Snake {
         };
 
         // make sure we always capture the start location
-        snake.tail_trail.insert(Loc::default());
+        snake.tail_trail.insert(Coord::default());
 
         snake
     }
@@ -102,7 +80,7 @@ impl Move for Snake {
         dest.apply_movement(direction);
         let dest = dest; // just to drop the mut
 
-        while self.body.borrow().head_loc().square_dist(&dest) > 0 {
+        while self.body.borrow().head_loc().chebyshev_dist(&dest) > 0 {
 
             {   
                 // using these braces to keep the mutable borrow 
@@ -110,26 +88,20 @@ impl Move for Snake {
                 // once we hit the first `curr_seg.borrow()`
 
                 let head = &mut self.body.borrow_mut().loc;
-                head.step_towards(&dest);
+                *head = head.step_towards(&dest);
             }
             
             let mut curr_seg = self.body.clone();
             let mut next_seg = curr_seg.borrow().next.clone();
-            loop {
-                
-                match next_seg {
-                    Some(seg) => {
-                        if seg.borrow().loc.square_dist(&curr_seg.borrow().loc) > 1 {
-                            seg.borrow_mut().loc.step_towards(&curr_seg.borrow().loc);
-                            // self.tail_trail.insert(self.tail.clone());
-                        }
-        
-                        next_seg = seg.borrow().next.clone();
-                        curr_seg = seg.clone();
-                    },
-                    None => break,
+            while let Some(seg) = next_seg {
+                if seg.borrow().loc.chebyshev_dist(&curr_seg.borrow().loc) > 1 {
+                    let next_loc = seg.borrow().loc.step_towards(&curr_seg.borrow().loc);
+                    seg.borrow_mut().loc = next_loc;
+                    // self.tail_trail.insert(self.tail.clone());
                 }
 
+                next_seg = seg.borrow().next.clone();
+                curr_seg = seg.clone();
             }
 
             self.tail_trail.insert(curr_seg.borrow().tail_loc());
@@ -138,7 +110,7 @@ impl Move for Snake {
 }
 
 struct SnakeSegment {
-    loc: Loc,
+    loc: Coord,
     next: Option<Rc<RefCell<SnakeSegment>>>,
 }
 
@@ -146,35 +118,38 @@ impl SnakeSegment {
 
     fn new(len: usize) -> Self {
         match len {
-            2..=usize::MAX => Self { loc: Loc::default(), next: Some(Rc::new(RefCell::new(Self::new(len-1)))) },
-            1 => Self { loc: Loc::default(), next: None },
+            2..=usize::MAX => Self { loc: Coord::default(), next: Some(Rc::new(RefCell::new(Self::new(len-1)))) },
+            1 => Self { loc: Coord::default(), next: None },
             _ => unreachable!(),
         }
     }
 
-    fn head_loc(&self) -> Loc {
-        self.loc.clone()
+    fn head_loc(&self) -> Coord {
+        self.loc
     }
 
-    fn tail_loc(&self) -> Loc {
+    fn tail_loc(&self) -> Coord {
         if let Some(next) = self.next.clone() {
             next.borrow().tail_loc()
         } else {
-            self.loc.clone()
+            self.loc
         }
     }
 
 }
 
-pub(crate) fn solve(mut input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
     
     let mut snake = match part {
         Part::Part1 => Snake::new(2),
         Part::Part2 => Snake::new(10),
     };
     
-    while let Some(line) = input.next() {
-        let movement: Movement = line.as_str().into();
+    // `validate` reports the movements that are skipped
+    for movement in parse::lines(input, Movement::parse_lenient) {
+        let Some(movement) = movement.unwrap_or_else(|e| panic!("{}", e)) else {
+            continue;
+        };
 
         snake.apply_movement(&movement);
     }
@@ -183,6 +158,9 @@ pub(crate) fn solve(mut input: Box<dyn Iterator<Item = String>>, part: Part) ->
 }
 
 
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    parse::validate_lines(input, Movement::parse)
+}
 
 
 #[test]
@@ -198,7 +176,7 @@ L 5
 R 2";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
     let output = solve(Box::new(lines.clone()), Part::Part1);
     assert_eq!(output.as_str(), "13");
@@ -217,7 +195,7 @@ L 25
 U 20";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
     
     let output = solve(Box::new(lines), Part::Part2);
@@ -225,19 +203,38 @@ U 20";
 }
 
 #[test]
-fn test_sq_dist() {
-    let loc1 = Loc::default();
-    assert!(loc1.square_dist(&loc1) == 0);
-
-    let loc2 = Loc{x: 0, y: 10};
-    assert!(loc1.square_dist(&loc2) == 10);
-    assert!(loc1.square_dist(&loc2) == loc2.square_dist(&loc1));
-
-    let loc2 = Loc{x: 1, y: 10};
-    assert!(loc1.square_dist(&loc2) == 10);
-    assert!(loc1.square_dist(&loc2) == loc2.square_dist(&loc1));
-
-    let loc2 = Loc{x: 10, y: 10};
-    assert!(loc1.square_dist(&loc2) == 10);
-    assert!(loc1.square_dist(&loc2) == loc2.square_dist(&loc1));
-}
\ No newline at end of file
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["R 4", "X 4", "L three", "D1", "R 4"], &[2, 3, 4]);
+}
+
+#[test]
+fn test_chebyshev_dist() {
+    let loc1 = Coord::default();
+    assert!(loc1.chebyshev_dist(&loc1) == 0);
+
+    let loc2 = Coord::new(0, 10);
+    assert!(loc1.chebyshev_dist(&loc2) == 10);
+    assert!(loc1.chebyshev_dist(&loc2) == loc2.chebyshev_dist(&loc1));
+
+    let loc2 = Coord::new(1, 10);
+    assert!(loc1.chebyshev_dist(&loc2) == 10);
+    assert!(loc1.chebyshev_dist(&loc2) == loc2.chebyshev_dist(&loc1));
+
+    let loc2 = Coord::new(10, 10);
+    assert!(loc1.chebyshev_dist(&loc2) == 10);
+    assert!(loc1.chebyshev_dist(&loc2) == loc2.chebyshev_dist(&loc1));
+}
+#[test]
+// a movement in an unknown direction doesn't move the rope
+fn test_unknown_direction() {
+    let lines = ["R 4", "X 4", "U 4"].into_iter().map(String::from);
+    assert_eq!(solve(Box::new(lines), Part::Part1), "7");
+}
+
+#[test]
+#[should_panic(expected = "line 2, column 3: expected ' ', found end of line")]
+// but one that isn't a movement at all is an error
+fn test_not_a_movement() {
+    let lines = ["R 4", "R4"].into_iter().map(String::from);
+    solve(Box::new(lines), Part::Part1);
+}
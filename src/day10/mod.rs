@@ -2,6 +2,8 @@
 use std::{collections::VecDeque, ops::{AddAssign, SubAssign}};
 
 use crate::Part;
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
 
 const START_CYCLE_PT1:usize = 20;
 const CYCLE_INTERVAL:usize = 40;
@@ -18,13 +20,14 @@ enum Instruction {
     Noop,
 }
 
-impl From<&str> for Instruction {
-    fn from(value: &str) -> Self {
-        if let Some((_left, right)) = value.split_once(' ') {
-            let x:isize = right.parse().unwrap_or(0);
-            Instruction::AddX(x)
+impl Instruction {
+    /// `noop` or `addx <x>`
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        let is_addx = c.one_of(&[("noop", false), ("addx ", true)])?;
+        if is_addx {
+            Ok(Instruction::AddX(c.integer()?))
         } else {
-            Instruction::Noop
+            Ok(Instruction::Noop)
         }
     }
 }
@@ -41,7 +44,7 @@ enum CycleState {
     WorkQueueEmpty,
 }
 
-struct CPU {
+struct Cpu {
     cycle: usize,
     register_x: isize,
     line_buffer: Vec<char>,
@@ -52,7 +55,7 @@ struct CPU {
     interrupt: Option<(Interrupt, CycleToFire)>,
 }
 
-impl CPU {
+impl Cpu {
 
     fn new() -> Self {
         Self { 
@@ -172,12 +175,15 @@ impl CPU {
 }
 
 
-pub(crate) fn solve(mut input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
 
-    let mut cpu = CPU::new();
+    let mut cpu = Cpu::new();
 
-    while let Some(line) = input.next() {
-        let instruction = Instruction::from(line.as_str());
+    for (i, line) in input.enumerate() {
+        // lines that don't parse still take up cycles, as they always have: an `addx` of 0 if it has an argument, a `noop` if not.
+        // `validate` is what reports them.
+        let instruction = parse::parse_line(&line, i + 1, Instruction::parse)
+            .unwrap_or_else(|_| if line.contains(' ') { Instruction::AddX(0) } else { Instruction::Noop });
         cpu.schedule_instruction(instruction);
     }
     
@@ -187,7 +193,7 @@ pub(crate) fn solve(mut input: Box<dyn Iterator<Item = String>>, part: Part) ->
     }
 }
 
-fn calc_signal_strength_totals(mut cpu: CPU, start: usize, interval: usize) -> String {
+fn calc_signal_strength_totals(mut cpu: Cpu, start: usize, interval: usize) -> String {
     
     cpu.install_interrupt(Interrupt { interval: start-1, repeats: false });
     cpu.run_until_interrupt();
@@ -195,31 +201,29 @@ fn calc_signal_strength_totals(mut cpu: CPU, start: usize, interval: usize) -> S
     cpu.install_interrupt(Interrupt { interval, repeats: true });
 
     let mut total = cpu.current_signal_strength();
-    loop {
-        match cpu.run_until_interrupt() {
-            CycleState::Interrupted => total += cpu.current_signal_strength(),
-            _ => break,
-        }
+    while let CycleState::Interrupted = cpu.run_until_interrupt() {
+        total += cpu.current_signal_strength();
     }
 
     format!("{}", total)
 }
 
-fn accumulate_line_buffers(mut cpu: CPU, _start: usize, interval: usize) -> String {
+fn accumulate_line_buffers(mut cpu: Cpu, _start: usize, interval: usize) -> String {
     
     let mut lines = String::new();
     cpu.install_interrupt(Interrupt { interval, repeats: true });
     
-    loop {
-        match cpu.run_until_interrupt() {
-            CycleState::Interrupted => lines += &cpu.get_line_buffer(),
-            _ => break,
-        }
+    while let CycleState::Interrupted = cpu.run_until_interrupt() {
+        lines += &cpu.get_line_buffer();
     }
 
     lines.trim_end().to_string()
 }
 
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    parse::validate_lines(input, Instruction::parse)
+}
+
 
 #[test]
 // sanity check vs example input
@@ -372,7 +376,7 @@ noop
 noop";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
     let output = solve(Box::new(lines.clone()), Part::Part1);
     assert_eq!(output.as_str(), "13140");
@@ -387,6 +391,11 @@ r"##..##..##..##..##..##..##..##..##..##..
 #######.......#######.......#######.....");
 }
 
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["noop", "addx -5", "addx", "nop", "addx 1.5"], &[3, 4, 5]);
+}
+
 #[cfg(test_output)]
 #[test]
 fn test_input_file() {
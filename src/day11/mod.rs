@@ -1,61 +1,40 @@
 
 use crate::Part;
+use crate::cycle;
+use crate::math::{self, ArithmeticError, Checked, Operand};
+use crate::parse::{self, Block, Cursor, ParseError, ParseResult};
+use crate::validate::Issue;
 
 const ROUNDS_P1:usize = 20;
 const ROUNDS_P2:usize = 10000;
 
 const TOP_N:usize = 2;
 
-#[derive(Default)]
-enum Operand {
-    #[default]
-    Add,
-    Sub,
-    Multi,
-    Divide,
+/// how the rounds are simulated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Simulation {
+    /// every monkey throws all of its items, round after round.
+    RoundByRound,
+    /// follows each item on its own, skipping ahead once its trip through the monkeys repeats.
+    /// only valid without calming, as it relies on reducing worry levels by the LCM.
+    CycleDetection,
 }
 
-impl From<&str> for Operand {
-    fn from(value: &str) -> Self {
-        match value {
-            "+" => Self::Add,
-            "-" => Self::Sub,
-            "*" => Self::Multi,
-            "/" => Self::Divide,
-            _ => Self::default(),
-        }
-    }
-}
-
-impl Operand {
-    fn do_op(&self, left:u64, right:u64) -> u64 {
-        match *self {
-            Self::Add => left + right,
-            Self::Sub => left - right,
-            Self::Multi => left * right,
-            Self::Divide => left / right,
-        }
-    }
-}
-
-#[derive(Default)]
 enum Value {
-    #[default]
     Variable,
     Constant(u64),
 }
 
-impl From<&str> for Value {
-    fn from(value: &str) -> Self {
-        let val = value.parse::<u64>();
-        match val {
-            Ok(x) => Self::Constant(x),
-            Err(_) => Self::Variable,
+impl Value {
+    /// `old` or a constant
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        if c.try_literal("old") {
+            Ok(Self::Variable)
+        } else {
+            Ok(Self::Constant(c.integer()?))
         }
     }
-}
 
-impl Value {
     fn const_or(&self, var: u64) -> u64 {
         match *self {
             Self::Constant(x) => x,
@@ -64,35 +43,37 @@ impl Value {
     }
 }
 
-#[derive(Default)]
 struct  Expression {
     left: Value,
     right: Value,
     op: Operand,
 }
 
-impl From<&str> for Expression {
-    fn from(value: &str) -> Self {
-        let (_, exp) = value.split_once("=")
-            .expect("expressions have an '='");
-
-        let mut chunks = exp.trim().split(" ");
-
-        let left = chunks.next().expect("need left side of expression").into();
-        let op = chunks.next().expect("need op of expression").into();
-        let right = chunks.next().expect("need left side of expression").into();
-
-        Self { left, right, op }
+impl Expression {
+    /// `new = <value> <op> <value>`
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        c.literal("new = ")?;
+        let left = Value::parse(c)?;
+        c.literal(" ")?;
+        let op = Operand::parse(c)?;
+        c.literal(" ")?;
+        let right = Value::parse(c)?;
+
+        Ok(Self { left, right, op })
     }
-}
 
-impl Expression {
-    fn calc_with_var(&self, x: u64) -> u64 {
+    fn calc_with_var(&self, x: u64) -> Result<u64, ArithmeticError> {
         self.op.do_op(
             self.left.const_or(x),
             self.right.const_or(x)
         )
     }
+
+    /// calculates modulo `m`, for when only divisibility by factors of `m` matters.
+    fn calc_with_var_mod(&self, x: u64, m: u64) -> u64 {
+        self.op.do_op_mod(self.left.const_or(x), self.right.const_or(x), m)
+            .expect("worry levels can't be divided once they're reduced by the LCM")
+    }
 }
 
 type MonkeyIndex = usize;
@@ -106,68 +87,142 @@ struct Monkey {
     option2: MonkeyIndex,
 }
 
-impl From<Vec<String>> for Monkey {
-    fn from(lines: Vec<String>) -> Self {
-        let mut items = Vec::default();
-        let mut operation = Expression::default();
-        let mut test_modulus = u64::default();
-        let mut option1: MonkeyIndex = usize::default();
-        let mut option2: MonkeyIndex = usize::default();
-
-        let mut lines = lines.iter();
-        while let Some(line) = lines.next() {
-            let (left,right) = line.trim().split_once(":")
-                .expect("all lines have :");
-            
-            match left {
-                _ if left.starts_with("Starting items") => {
-                    items = right.split(",")
-                        .filter_map(|c| c.trim().parse().ok())
-                        .collect();
-                },
-                _ if left.starts_with("Operation") => operation = right.into(),
-                _ if left.starts_with("Test") => {
-                    let (_, right) = right
-                        .split_once(" by ")
-                        .expect("invalid 'Test' format");
-                    test_modulus = right.trim().parse().expect("'Test' should be usize");
-                },
-                _ if left.starts_with("If true") => {
-                    let (_, right) = right
-                        .split_once(" monkey ")
-                        .expect("invalid 'If' format");
-                    option1 = right.trim().parse().expect("'If' should be usize");
-                },
-                _ if left.starts_with("If false") => {
-                    let (_, right) = right
-                        .split_once(" monkey ")
-                        .expect("invalid 'If' format");
-                    option2 = right.trim().parse().expect("'If' should be usize");
-                },
-                _ => {},
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FieldName {
+    StartingItems,
+    Operation,
+    Test,
+    IfTrue,
+    IfFalse,
+}
+
+const MONKEY_FIELDS: [(&str, FieldName); 5] = [
+    ("Starting items", FieldName::StartingItems),
+    ("Operation", FieldName::Operation),
+    ("Test", FieldName::Test),
+    ("If true", FieldName::IfTrue),
+    ("If false", FieldName::IfFalse),
+];
+
+/// one of the indented lines describing a monkey.
+enum Field {
+    StartingItems(Vec<u64>),
+    Operation(Expression),
+    Test(u64),
+    IfTrue(MonkeyIndex),
+    IfFalse(MonkeyIndex),
+}
+
+impl Field {
+    /// `<field>: <value>`
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        c.whitespace();
+        let name = c.one_of(&MONKEY_FIELDS)?;
+        c.literal(":")?;
+        c.whitespace();
+
+        match name {
+            FieldName::StartingItems if c.is_at_end() => Ok(Field::StartingItems(Vec::new())),
+            FieldName::StartingItems => Ok(Field::StartingItems(c.separated(", ", |c| c.integer())?)),
+            FieldName::Operation => Ok(Field::Operation(Expression::parse(c)?)),
+            FieldName::Test => {
+                c.literal("divisible by ")?;
+                match c.integer()? {
+                    0 => Err(c.error("can't test divisibility by 0")),
+                    n => Ok(Field::Test(n)),
+                }
+            },
+            FieldName::IfTrue | FieldName::IfFalse => {
+                c.literal("throw to monkey ")?;
+                let target = c.integer()?;
+                Ok(if name == FieldName::IfTrue { Field::IfTrue(target) } else { Field::IfFalse(target) })
+            },
+        }
+    }
+
+    fn name(&self) -> FieldName {
+        match self {
+            Field::StartingItems(_) => FieldName::StartingItems,
+            Field::Operation(_) => FieldName::Operation,
+            Field::Test(_) => FieldName::Test,
+            Field::IfTrue(_) => FieldName::IfTrue,
+            Field::IfFalse(_) => FieldName::IfFalse,
+        }
+    }
+}
+
+/// `Monkey <n>:`
+fn parse_header(c: &mut Cursor) -> ParseResult<MonkeyIndex> {
+    c.literal("Monkey ")?;
+    let id = c.integer()?;
+    c.literal(":")?;
+    Ok(id)
+}
+
+/// the fields in `block` that are never given.
+fn missing_fields(block: &Block, seen: &[FieldName]) -> Vec<ParseError> {
+    MONKEY_FIELDS.iter()
+        .filter(|(_, name)| !seen.contains(name))
+        .map(|(label, _)| block.error(format!("monkey is missing its '{}' line", label)))
+        .collect()
+}
+
+impl Monkey {
+    fn parse(block: &Block) -> ParseResult<Self> {
+        let mut lines = block.numbered();
+        if let Some((line_no, header)) = lines.next() {
+            parse::parse_line(header, line_no, parse_header)?;
+        }
+
+        let (mut items, mut operation, mut test_modulus, mut option1, mut option2) = (None, None, None, None, None);
+        let mut seen: Vec<FieldName> = Vec::new();
+        for (line_no, line) in lines {
+            let field = parse::parse_line(line, line_no, Field::parse)?;
+            seen.push(field.name());
+
+            match field {
+                Field::StartingItems(v) => items = Some(v),
+                Field::Operation(v) => operation = Some(v),
+                Field::Test(v) => test_modulus = Some(v),
+                Field::IfTrue(v) => option1 = Some(v),
+                Field::IfFalse(v) => option2 = Some(v),
             }
         }
 
-        Monkey { total_inspections: 0, items, operation, test_modulus, option1, option2 }
+        let (Some(items), Some(operation), Some(test_modulus), Some(option1), Some(option2)) =
+            (items, operation, test_modulus, option1, option2) else {
+            return Err(missing_fields(block, &seen).remove(0));
+        };
+
+        Ok(Monkey { total_inspections: 0, items, operation, test_modulus, option1, option2 })
     }
 }
 
+/// what happens to worry levels after each inspection.
+#[derive(Clone, Copy)]
+enum Relief {
+    /// divided by 3, so the levels stay small enough to track exactly.
+    Calming,
+    /// no relief, so the levels are kept modulo the LCM of the test divisors instead.
+    /// (which keeps every divisibility test's result the same)
+    ReducedBy(u64),
+}
+
 impl Monkey {
-    fn do_monkey_business(&mut self, do_calming: bool) -> Vec<(u64, MonkeyIndex)> {
+    fn do_monkey_business(&mut self, relief: Relief) -> Result<Vec<(u64, MonkeyIndex)>, ArithmeticError> {
         let mut num_inspections = 0_usize;
         let mut thrown_items = Vec::default();
 
         while let Some(item) = self.items.pop() {
             // inspect (apply worry level change operation)
-            let mut new_item = self.operation.calc_with_var(item);
+            let new_item = match relief {
+                Relief::Calming => self.operation.calc_with_var(item)? / 3,
+                Relief::ReducedBy(lcm) => self.operation.calc_with_var_mod(item, lcm),
+            };
             num_inspections += 1;
-            
-            if do_calming {
-                new_item /= 3;
-            }
 
             // test
-            let monkey_index = if new_item % self.test_modulus == 0 {
+            let monkey_index = if new_item.is_multiple_of(self.test_modulus) {
                 self.option1
             } else {
                 self.option2
@@ -177,50 +232,111 @@ impl Monkey {
         }
         self.total_inspections += num_inspections;
 
-        thrown_items
+        Ok(thrown_items)
     }
 }
 
-fn chunker(input: &mut Box<dyn Iterator<Item = String>>, delimiter: &str) -> Option<Vec<String>> {
-    let mut lines:Vec<String> = Vec::new();
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    // (line, target monkey) to check once we know how many monkeys there are
+    let mut targets: Vec<(usize, usize)> = Vec::new();
+    let mut num_monkeys = 0;
+
+    for block in parse::blocks(input) {
+        let mut lines = block.numbered();
+        if let Some((line_no, header)) = lines.next() {
+            match parse::parse_line(header, line_no, parse_header) {
+                Ok(id) if id == num_monkeys => {},
+                Ok(_) => issues.push(Issue::new(line_no, format!("expected 'Monkey {}:', found '{}'", num_monkeys, header.trim()))),
+                Err(e) => issues.push(e.into()),
+            }
+        }
+        num_monkeys += 1;
+
+        let mut seen: Vec<FieldName> = Vec::new();
+        for (line_no, line) in lines {
+            let field = match parse::parse_line(line, line_no, Field::parse) {
+                Ok(field) => field,
+                Err(e) => {
+                    // still count the field as given, so it isn't reported as missing as well
+                    if let Some((_, name)) = MONKEY_FIELDS.iter().find(|(label, _)| line.trim_start().starts_with(label)) {
+                        seen.push(*name);
+                    }
+                    issues.push(e.into());
+                    continue;
+                },
+            };
 
-    while let Some(line) = input.next() {
-        if line.as_str() == delimiter {
-            break;
+            if seen.contains(&field.name()) {
+                issues.push(Issue::new(line_no, "duplicate field"));
+                continue;
+            }
+            seen.push(field.name());
+
+            if let Field::IfTrue(target) | Field::IfFalse(target) = field {
+                targets.push((line_no, target));
+            }
         }
-        
-        lines.push(line);
-    }
 
-    if lines.len() == 0 {
-        None
-    } else {
-        Some(lines)
+        issues.extend(missing_fields(&block, &seen).into_iter().map(Issue::from));
     }
+
+    targets.into_iter()
+        .filter(|(_, target)| *target >= num_monkeys)
+        .for_each(|(line_no, target)| issues.push(Issue::new(line_no, format!("there is no monkey {}", target))));
+
+    issues.sort_by_key(|i| i.line);
+    issues
 }
 
-pub(crate) fn solve(mut input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    // following items through their cycles is ~10x faster on a real input
+    let simulation = match part {
+        Part::Part1 => Simulation::RoundByRound,
+        Part::Part2 => Simulation::CycleDetection,
+    };
+    solve_with(input, part, simulation)
+}
+
+/// runs every round, instead of following items through their cycles.
+pub(crate) fn solve_brute_force(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    solve_with(input, part, Simulation::RoundByRound)
+}
 
-    let mut monkey_list:Vec<Monkey> = Vec::new();
+fn solve_with(input: Box<dyn Iterator<Item = String>>, part: Part, simulation: Simulation) -> String {
 
-    while let Some(monkey_info) = chunker(&mut input, "")  {
-        monkey_list.push(monkey_info.into());
-    }
+    let monkey_list:Vec<Monkey> = parse::blocks(input)
+        .map(|block| Monkey::parse(&block).unwrap_or_else(|e| panic!("{}", e)))
+        .collect();
 
-    let total = match part {
-        Part::Part1 => calc_top_n_monkey_business(monkey_list, ROUNDS_P1, true),
-        Part::Part2 => calc_top_n_monkey_business(monkey_list, ROUNDS_P2, false),
+    let total = match (part, simulation) {
+        // calming doesn't survive reducing by the LCM, so part 1 always runs round by round
+        (Part::Part1, _) => calc_top_n_monkey_business(monkey_list, ROUNDS_P1, Relief::Calming),
+        (Part::Part2, Simulation::RoundByRound) => {
+            let lcm = calc_lcm(&monkey_list);
+            calc_top_n_monkey_business(monkey_list, ROUNDS_P2, Relief::ReducedBy(lcm))
+        },
+        (Part::Part2, Simulation::CycleDetection) => calc_top_n_by_cycles(monkey_list, ROUNDS_P2),
     };
 
     format!("{}", total)
 }
 
-fn calc_top_n_monkey_business(mut monkey_list: Vec<Monkey>, num_rounds: usize, do_calming: bool) -> u64 {
+fn calc_lcm(monkey_list: &[Monkey]) -> u64 {
+    math::lcm_all(monkey_list.iter().map(|m| m.test_modulus))
+        .expect("the LCM of the test divisors should fit in a u64")
+}
+
+/// the product of the `TOP_N` inspection counts.
+fn top_n_product(mut inspections: Vec<u64>) -> u64 {
+    inspections.sort_by_key(|n| std::cmp::Reverse(*n));
+    inspections.iter()
+        .take(TOP_N)
+        .try_fold(1_u64, |acc, n| acc.mul_checked(*n))
+        .unwrap_or_else(|e| panic!("monkey business is too big: {}", e))
+}
 
-    // calc LCM
-    let lcm = monkey_list.iter()
-        .map(|m| m.test_modulus)
-        .fold(1, |acc, x| acc * x);
+fn calc_top_n_monkey_business(mut monkey_list: Vec<Monkey>, num_rounds: usize, relief: Relief) -> u64 {
 
     // run the sim
     for _round in 0..num_rounds {
@@ -228,27 +344,60 @@ fn calc_top_n_monkey_business(mut monkey_list: Vec<Monkey>, num_rounds: usize, d
         for idx in 0..monkey_list.len() {
             let m = monkey_list.get_mut(idx)
                 .expect("already checked bounds");
-            let thrown  = m.do_monkey_business(do_calming);
+            let thrown = m.do_monkey_business(relief)
+                .unwrap_or_else(|e| panic!("monkey {} can't track its worry levels: {}", idx, e));
 
             for (item, idx) in thrown {
-
-                let adj_item = if item > lcm { item % lcm } else { item };
-
                 if let Some(other_monkey) = monkey_list.get_mut(idx) {
-                    other_monkey.items.push(adj_item);
+                    other_monkey.items.push(item);
                 }
             }
         }
 
     }
 
-    // calc the top n
-    monkey_list.sort_by(|a,b| b.total_inspections.cmp(&a.total_inspections));
-    let _discard_end = monkey_list.split_off(TOP_N);
-
     // return the product of the top monkeys total inspections
-    monkey_list.iter()
-        .fold(1_u64, |acc, m| acc * m.total_inspections as u64)
+    top_n_product(monkey_list.iter().map(|m| m.total_inspections as u64).collect())
+}
+
+/// one round for a single item held by `monkey`: it's inspected, then thrown on
+/// (being inspected again this round if it lands on a monkey that hasn't had its turn yet).
+/// returns where the item ends up, and the inspections each monkey made.
+fn item_round(monkey_list: &[Monkey], (mut monkey, mut worry): (MonkeyIndex, u64), lcm: u64) -> ((MonkeyIndex, u64), Vec<u64>) {
+    let mut inspections = vec![0_u64; monkey_list.len()];
+
+    loop {
+        let m = &monkey_list[monkey];
+        inspections[monkey] += 1;
+        worry = m.operation.calc_with_var_mod(worry, lcm);
+
+        let next = if worry.is_multiple_of(m.test_modulus) { m.option1 } else { m.option2 };
+        let same_round = next > monkey;
+        monkey = next;
+
+        if !same_round {
+            return ((monkey, worry), inspections);
+        }
+    }
+}
+
+fn calc_top_n_by_cycles(monkey_list: Vec<Monkey>, num_rounds: usize) -> u64 {
+
+    let lcm = calc_lcm(&monkey_list);
+
+    // each item's trip only depends on its holder & worry level, so they can be followed one at a time
+    let inspections: Vec<u64> = monkey_list.iter()
+        .enumerate()
+        .flat_map(|(idx, m)| m.items.iter().map(move |item| (idx, *item % lcm)))
+        .map(|item| cycle::extrapolate(
+            item,
+            num_rounds as u64,
+            |item| item_round(&monkey_list, *item, lcm),
+            |item| *item,
+        ))
+        .fold(Vec::new(), |acc, v| cycle::Accumulate::add(&acc, &v));
+
+    top_n_product(inspections)
 }
 
 
@@ -284,11 +433,90 @@ Monkey 3:
         If false: throw to monkey 1";
 
     let lines = EXAMPLE.split('\n')
-        .map(|item| String::from(item));
+        .map(String::from);
 
     let output = solve(Box::new(lines.clone()), Part::Part1);
     assert_eq!(output.as_str(), "10605");
     
-    let output = solve(Box::new(lines), Part::Part2);
+    let output = solve(Box::new(lines.clone()), Part::Part2);
     assert_eq!(output.as_str(), "2713310158");
+
+    let output = solve_brute_force(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "2713310158");
+}
+
+#[test]
+// worry levels used to be reduced by the LCM before calming too, which changes
+// which monkey an item goes to once levels grow past the LCM (this gave 361).
+fn test_calming_is_exact() {
+    const EXAMPLE: &str = r"Monkey 0:
+  Starting items: 29
+  Operation: new = old * 3
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 2
+
+Monkey 1:
+  Starting items:
+  Operation: new = old + 1
+  Test: divisible by 2
+    If true: throw to monkey 0
+    If false: throw to monkey 2
+
+Monkey 2:
+  Starting items:
+  Operation: new = old + 2
+  Test: divisible by 7
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines), Part::Part1);
+    assert_eq!(output.as_str(), "342");
+}
+
+#[test]
+fn test_validate() {
+    const EXAMPLE: &str = r"Monkey 0:
+    Starting items: 79, 98
+    Operation: new = old ^ 19
+    Test: divisible by 23
+        If true: throw to monkey 2
+        If false: throw to monkey 1
+
+Monkey 1:
+    Starting items: 54, 6x
+    Operation: new = old + 6
+    Test: divisible by 19
+        If true: throw to monkey 0";
+
+    let lines: Vec<&str> = EXAMPLE.split('\n').collect();
+
+    let issues = crate::validate::assert_issue_lines(validate, &lines, &[3, 5, 8, 9]);
+    assert!(issues[2].message.contains("If false"));
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow in '5 - 100'")]
+fn test_underflow_is_reported() {
+    const EXAMPLE: &str = r"Monkey 0:
+    Starting items: 5
+    Operation: new = old - 100
+    Test: divisible by 2
+        If true: throw to monkey 1
+        If false: throw to monkey 1
+
+Monkey 1:
+    Starting items: 1
+    Operation: new = old + 1
+    Test: divisible by 3
+        If true: throw to monkey 0
+        If false: throw to monkey 0";
+
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    solve(Box::new(lines), Part::Part1);
 }
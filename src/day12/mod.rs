@@ -0,0 +1,185 @@
+
+use crate::Part;
+use crate::grid::{self, Coord, Direction, Grid};
+use crate::search::{self, Found};
+use crate::validate::Issue;
+
+const START: char = 'S';
+const END: char = 'E';
+
+fn elevation(c: char) -> u8 {
+    match c {
+        START => b'a',
+        END => b'z',
+        c => c as u8,
+    }
+}
+
+fn is_square(c: char) -> bool {
+    c.is_ascii_lowercase() || c == START || c == END
+}
+
+struct Heightmap {
+    map: Grid<char>,
+    start: Coord,
+    end: Coord,
+}
+
+impl Heightmap {
+    fn parse(input: Box<dyn Iterator<Item = String>>) -> Self {
+        let map = Grid::parse(input, |c| is_square(c).then_some(c))
+            .unwrap_or_else(|e| panic!("invalid heightmap: {}", e));
+
+        let start = map.find(|c| *c == START).expect("the heightmap should have a start 'S'");
+        let end = map.find(|c| *c == END).expect("the heightmap should have an end 'E'");
+
+        Self { map, start, end }
+    }
+
+    /// squares we can step to from `loc` (climbing at most one level).
+    fn climbable_from(&self, loc: &Coord) -> Vec<Coord> {
+        let max_elevation = elevation(self.map[*loc]) + 1;
+
+        self.map.neighbours4(*loc)
+            .filter(|n| elevation(self.map[*n]) <= max_elevation)
+            .collect()
+    }
+
+    fn shortest_path(&self, part: &Part) -> Found<Coord> {
+        let starts: Vec<Coord> = match part {
+            Part::Part1 => vec![self.start],
+            Part::Part2 => self.map.iter()
+                .filter(|(_, c)| elevation(**c) == b'a')
+                .map(|(loc, _)| loc)
+                .collect(),
+        };
+
+        search::bfs(starts, |loc| self.climbable_from(loc), |loc| *loc == self.end)
+            .expect("there should be a path to the end 'E'")
+    }
+
+    /// draws `path` over the map, like the puzzle text does:
+    /// each square on the path points to the next one, and everything else is a '.'
+    fn render_path(&self, path: &[Coord]) -> String {
+        let mut overlay = self.map.map(|_| '.');
+
+        for step in path.windows(2) {
+            let direction = Direction::ALL.into_iter()
+                .find(|d| step[0].step(*d) == step[1])
+                .expect("path steps should be orthogonal neighbours");
+
+            overlay[step[0]] = match direction {
+                Direction::Up => '^',
+                Direction::Down => 'v',
+                Direction::Left => '<',
+                Direction::Right => '>',
+            };
+        }
+        overlay[self.end] = END;
+
+        overlay.render(|c| *c)
+    }
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    let heightmap = Heightmap::parse(input);
+
+    format!("{}", heightmap.shortest_path(&part).cost)
+}
+
+/// also draws the shortest path over the map.
+pub(crate) fn explain(input: Box<dyn Iterator<Item = String>>, part: Part) -> (String, String) {
+    let heightmap = Heightmap::parse(input);
+    let found = heightmap.shortest_path(&part);
+
+    (format!("{}", found.cost), heightmap.render_path(&found.path))
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut width: Option<usize> = None;
+    let (mut start_line, mut end_line): (Option<usize>, Option<usize>) = (None, None);
+
+    for (i, line) in grid::grid_lines(input).into_iter().enumerate() {
+        let line_no = i + 1;
+
+        if let Some((col, c)) = line.chars().enumerate().find(|(_, c)| !is_square(*c)) {
+            issues.push(Issue::new(line_no, format!("squares should be 'a'-'z', 'S' or 'E', found '{}' at column {}", c, col + 1)));
+        }
+
+        // a line can hold more than one marker, so every one after the first is a duplicate
+        for (marker, seen) in [(START, &mut start_line), (END, &mut end_line)] {
+            for _ in line.matches(marker) {
+                match seen {
+                    Some(_) => issues.push(Issue::new(line_no, format!("there should only be one '{}'", marker))),
+                    None => *seen = Some(line_no),
+                }
+            }
+        }
+
+        let len = line.chars().count();
+        match width {
+            None if len == 0 => issues.push(Issue::new(line_no, "row is empty")),
+            None => width = Some(len),
+            Some(w) if w != len => issues.push(Issue::new(line_no, format!("row is {} squares wide, expected {}", len, w))),
+            Some(_) => {},
+        }
+    }
+
+    for (marker, seen) in [(START, start_line), (END, end_line)] {
+        if seen.is_none() {
+            issues.push(Issue::new(1, format!("the heightmap has no '{}'", marker)));
+        }
+    }
+
+    issues.sort_by_key(|i| i.line);
+    issues
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = r"Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "31");
+
+    let output = solve(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "29");
+}
+
+#[test]
+fn test_explain() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let (answer, overlay) = explain(Box::new(lines), Part::Part1);
+    assert_eq!(answer.as_str(), "31");
+
+    let rows: Vec<&str> = overlay.split('\n').collect();
+    assert_eq!(rows.len(), 5);
+    assert!(rows[0].starts_with('v') || rows[0].starts_with('>'));
+    assert_eq!(rows[2].chars().nth(5), Some('E'));
+    // one arrow per step
+    assert_eq!(overlay.chars().filter(|c| "^v<>".contains(*c)).count(), 31);
+}
+
+#[test]
+fn test_validate() {
+    // no 'E' is reported on the first line
+    crate::validate::assert_issue_lines(validate, &["Sabqponm", "abcryxxl", "accsz?xk", "acctuvwj", "abdSfgh"], &[1, 3, 5, 5]);
+    // both markers on one line, twice
+    crate::validate::assert_issue_lines(validate, &["SabSqE", "abEcry"], &[1, 2]);
+    // like `solve`, blank lines at the end are fine
+    crate::validate::assert_issue_lines(validate, &["Sab", "abE", ""], &[]);
+}
@@ -0,0 +1,209 @@
+
+use std::{cmp::Ordering, fmt::Display};
+
+use crate::Part;
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
+
+const DIVIDER_PACKETS: [&str; 2] = ["[[2]]", "[[6]]"];
+
+#[derive(Debug, Clone)]
+enum Packet {
+    Integer(u32),
+    List(Vec<Packet>),
+}
+
+impl Packet {
+    /// an integer, or a bracketed & comma separated list of packets (ie: `[1,[2,[]]]`)
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        if !c.try_literal("[") {
+            return Ok(Packet::Integer(c.integer()?));
+        }
+        if c.try_literal("]") {
+            return Ok(Packet::List(Vec::new()));
+        }
+
+        let items = c.separated(",", Packet::parse)?;
+        c.literal("]")?;
+        Ok(Packet::List(items))
+    }
+
+    fn from_line(line: &str, line_no: usize) -> Self {
+        parse::parse_line(line, line_no, Packet::parse)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Integer(left), Packet::Integer(right)) => left.cmp(right),
+            // lists compare item by item, and then the shorter list comes first
+            (Packet::List(left), Packet::List(right)) => left.cmp(right),
+            // a lone integer compares as a list holding just that integer
+            (Packet::Integer(left), Packet::List(right)) => [Packet::Integer(*left)].as_slice().cmp(right.as_slice()),
+            (Packet::List(left), Packet::Integer(right)) => left.as_slice().cmp([Packet::Integer(*right)].as_slice()),
+        }
+    }
+}
+
+// equality has to agree with the ordering, ie: `[[2]]` & `[2]` are equal packets
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Packet {}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Integer(n) => f.write_fmt(format_args!("{}", n)),
+            Packet::List(items) => {
+                let items: Vec<String> = items.iter().map(|p| p.to_string()).collect();
+                f.write_fmt(format_args!("[{}]", items.join(",")))
+            },
+        }
+    }
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+
+    let pairs: Vec<(Packet, Packet)> = parse::blocks(input)
+        .map(|block| {
+            let mut packets = block.numbered().map(|(line_no, line)| Packet::from_line(line, line_no));
+            match (packets.next(), packets.next(), packets.next()) {
+                (Some(left), Some(right), None) => (left, right),
+                _ => panic!("line {}: packets should come in pairs", block.first_line),
+            }
+        })
+        .collect();
+
+    let total = match part {
+        Part::Part1 => sum_ordered_pair_indices(&pairs),
+        Part::Part2 => calc_decoder_key(&pairs),
+    };
+    format!("{}", total)
+}
+
+// part 1 calculations
+fn sum_ordered_pair_indices(pairs: &[(Packet, Packet)]) -> usize {
+    pairs.iter()
+        .enumerate()
+        .filter(|(_, (left, right))| left < right)
+        .map(|(i, _)| i + 1)
+        .sum()
+}
+
+// part 2 calculations
+fn calc_decoder_key(pairs: &[(Packet, Packet)]) -> usize {
+    // no need to sort, a divider's index is just how many packets come before it.
+    // the dividers are in order, so each one also comes after the dividers ahead of it (& indices start at 1)
+    DIVIDER_PACKETS.iter()
+        .map(|p| Packet::from_line(p, 0))
+        .enumerate()
+        .map(|(i, divider)| {
+            let before = pairs.iter()
+                .flat_map(|(left, right)| [left, right])
+                .filter(|packet| **packet < divider)
+                .count();
+            before + i + 1
+        })
+        .product()
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+
+    for block in parse::blocks(input) {
+        if block.lines.len() != 2 {
+            issues.push(Issue::new(block.first_line, format!("expected a pair of packets, found {} line(s)", block.lines.len())));
+        }
+
+        issues.extend(
+            block.numbered()
+                .filter_map(|(line_no, line)| parse::parse_line(line, line_no, Packet::parse).err())
+                .map(Issue::from)
+        );
+    }
+
+    issues
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = r"[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "13");
+
+    let output = solve(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "140");
+}
+
+#[test]
+fn test_round_trip() {
+    for line in EXAMPLE.split('\n').filter(|l| !l.is_empty()) {
+        assert_eq!(Packet::from_line(line, 1).to_string(), line);
+    }
+}
+
+#[test]
+fn test_ordering() {
+    let packet = |s: &str| Packet::from_line(s, 1);
+
+    assert!(packet("[[1],[2,3,4]]") < packet("[[1],4]"));
+    assert!(packet("[9]") > packet("[[8,7,6]]"));
+    assert!(packet("[[[]]]") > packet("[[]]"));
+    assert_eq!(packet("[[2]]").cmp(&packet("[2]")), Ordering::Equal);
+    assert_eq!(packet("[[2]]"), packet("[2]"));
+    assert_ne!(packet("[[2]]"), packet("[[2],[]]"));
+}
+
+#[test]
+// a packet equal to a divider (ie: `[2]` & `[[2]]`) doesn't push it back, only packets that come strictly before it count
+fn test_decoder_key_ties() {
+    let lines = ["[2]", "[6]", "", "[[[2]]]", "[[6],1]"].into_iter().map(String::from);
+    assert_eq!(solve(Box::new(lines), Part::Part2), "4");
+}
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["[1,1]", "[1,2]", "", "[1,[2]", "[3]", "", "[4]", "", "[a]", "[]"], &[4, 7, 9]);
+}
@@ -0,0 +1,230 @@
+
+use crate::Part;
+use crate::grid::{Coord, Grid};
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
+
+/// where the sand pours in from.
+const SOURCE: Coord = Coord::new(500, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Air,
+    Rock,
+    Sand,
+}
+
+/// `x,y`
+fn parse_point(c: &mut Cursor) -> ParseResult<Coord> {
+    let x = c.integer()?;
+    c.literal(",")?;
+    let y = c.integer()?;
+    Ok(Coord::new(x, y))
+}
+
+/// a rock path, ie: `498,4 -> 498,6 -> 496,6`
+fn parse_path(c: &mut Cursor) -> ParseResult<Vec<Coord>> {
+    let start = c.clone();
+    let path = c.separated(" -> ", parse_point)?;
+
+    if path.iter().any(|p| p.y < 0) {
+        return Err(start.error("rocks can't be above the sand source"));
+    }
+    if path.windows(2).any(|seg| seg[0].x != seg[1].x && seg[0].y != seg[1].y) {
+        return Err(start.error("path segments should be horizontal or vertical"));
+    }
+    Ok(path)
+}
+
+enum Fall {
+    To(Coord),
+    Rest,
+    Abyss,
+}
+
+struct Cave {
+    /// `grid[0,0]` is at `(left, 0)` in the puzzle's coordinates.
+    grid: Grid<Cell>,
+    left: isize,
+    lowest_rock: isize,
+    has_floor: bool,
+}
+
+impl Cave {
+    fn parse(input: Box<dyn Iterator<Item = String>>, has_floor: bool) -> Self {
+        let paths: Vec<Vec<Coord>> = parse::lines(input, parse_path)
+            .map(|p| p.unwrap_or_else(|e| panic!("{}", e)))
+            .collect();
+
+        let points = || paths.iter().flatten();
+        let lowest_rock = points().map(|p| p.y).max().unwrap_or(0);
+
+        // with a floor, the sand piles up into a triangle as wide as it is tall.
+        let height = lowest_rock + 3;
+        let left = points().map(|p| p.x).min().unwrap_or(SOURCE.x).min(SOURCE.x - height) - 1;
+        let right = points().map(|p| p.x).max().unwrap_or(SOURCE.x).max(SOURCE.x + height) + 1;
+
+        let mut cave = Self {
+            grid: Grid::new((right - left + 1) as usize, height as usize, Cell::Air),
+            left,
+            lowest_rock,
+            has_floor,
+        };
+
+        for path in &paths {
+            for seg in path.windows(2) {
+                let mut rock = seg[0];
+                cave.set(rock, Cell::Rock);
+                while rock != seg[1] {
+                    rock = rock.step_towards(&seg[1]);
+                    cave.set(rock, Cell::Rock);
+                }
+            }
+            if let [only] = path.as_slice() {
+                cave.set(*only, Cell::Rock);
+            }
+        }
+        if has_floor {
+            for x in left..=right {
+                cave.set(Coord::new(x, height - 1), Cell::Rock);
+            }
+        }
+
+        cave
+    }
+
+    fn to_grid(&self, c: Coord) -> Coord {
+        Coord::new(c.x - self.left, c.y)
+    }
+
+    fn get(&self, c: Coord) -> Option<Cell> {
+        self.grid.get(self.to_grid(c)).copied()
+    }
+
+    fn set(&mut self, c: Coord, cell: Cell) {
+        let at = self.to_grid(c);
+        self.grid[at] = cell;
+    }
+
+    /// where a grain of sand at `grain` goes next: down, then down & left, then down & right.
+    fn fall(&self, grain: Coord) -> Fall {
+        if !self.has_floor && grain.y > self.lowest_rock {
+            return Fall::Abyss;
+        }
+
+        [0, -1, 1].into_iter()
+            .map(|dx| Coord::new(grain.x + dx, grain.y + 1))
+            .find(|next| self.get(*next) == Some(Cell::Air))
+            .map_or(Fall::Rest, Fall::To)
+    }
+
+    /// pours sand until it flows into the abyss or blocks the source, returning how many grains came to rest.
+    fn pour(&mut self) -> usize {
+        // each grain follows the one before it until they part ways,
+        // so resume from where the last grain's path was still open.
+        let mut path = vec![SOURCE];
+        let mut rested = 0;
+
+        while let Some(&grain) = path.last() {
+            match self.fall(grain) {
+                Fall::To(next) => path.push(next),
+                Fall::Rest => {
+                    self.set(grain, Cell::Sand);
+                    rested += 1;
+                    path.pop();
+                },
+                Fall::Abyss => break,
+            }
+        }
+
+        rested
+    }
+
+    /// draws the part of the cave with rock or sand in it, like the puzzle text does.
+    fn render(&self) -> String {
+        let height = if self.has_floor { self.grid.height() } else { self.lowest_rock as usize + 1 };
+
+        // the floor runs on forever, so it doesn't count towards how wide the drawing is.
+        let columns: Vec<usize> = (0..self.grid.width())
+            .filter(|x| self.grid.col(*x).take(self.lowest_rock as usize + 2).any(|c| *c != Cell::Air))
+            .collect();
+        let source = self.to_grid(SOURCE).x as usize;
+        let first = columns.first().map_or(source, |x| (*x).min(source));
+        let last = columns.last().map_or(source, |x| (*x).max(source));
+
+        self.grid.rows()
+            .take(height)
+            .enumerate()
+            .map(|(y, row)| (first..=last)
+                .map(|x| match row[x] {
+                    Cell::Rock => '#',
+                    Cell::Sand => 'o',
+                    Cell::Air if y == 0 && x == source => '+',
+                    Cell::Air => '.',
+                })
+                .collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    let mut cave = Cave::parse(input, part == Part::Part2);
+
+    format!("{}", cave.pour())
+}
+
+/// also draws the cave once the sand has settled.
+pub(crate) fn explain(input: Box<dyn Iterator<Item = String>>, part: Part) -> (String, String) {
+    let mut cave = Cave::parse(input, part == Part::Part2);
+    let rested = cave.pour();
+
+    (format!("{}", rested), cave.render())
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    parse::validate_lines(input, parse_path)
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = r"498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "24");
+
+    let output = solve(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "93");
+}
+
+#[test]
+fn test_explain() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let (answer, cave) = explain(Box::new(lines), Part::Part1);
+    assert_eq!(answer.as_str(), "24");
+    assert_eq!(cave.as_str(), r"......+...
+..........
+......o...
+.....ooo..
+....#ooo##
+...o#ooo#.
+..###ooo#.
+....oooo#.
+.o.ooooo#.
+#########.");
+}
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["498,4 -> 498,6", "498,4 -> 497,5", "", "498,-1 -> 498,2", "498,4 => 498,6"], &[2, 3, 4, 5]);
+}
@@ -0,0 +1,145 @@
+
+use std::collections::HashSet;
+
+use crate::Part;
+use crate::grid::Coord;
+use crate::interval::{Interval, IntervalSet};
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
+
+/// the row to check in part 1.
+const ROW: i64 = 2_000_000;
+/// the distress beacon is somewhere in `0..=BOUND` (in both x & y) in part 2.
+const BOUND: i64 = 4_000_000;
+
+struct Sensor {
+    at: Coord,
+    beacon: Coord,
+    /// everything this close to the sensor would have been detected instead of `beacon`.
+    radius: i64,
+}
+
+impl Sensor {
+    /// `x=2, y=18`
+    fn parse_position(c: &mut Cursor) -> ParseResult<Coord> {
+        c.literal("x=")?;
+        let x = c.integer()?;
+        c.literal(", y=")?;
+        let y = c.integer()?;
+        Ok(Coord::new(x, y))
+    }
+
+    /// `Sensor at x=2, y=18: closest beacon is at x=-2, y=15`
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        c.literal("Sensor at ")?;
+        let at = Sensor::parse_position(c)?;
+        c.literal(": closest beacon is at ")?;
+        let beacon = Sensor::parse_position(c)?;
+
+        Ok(Self { at, beacon, radius: at.manhattan_dist(&beacon) as i64 })
+    }
+
+    /// the part of row `y` this sensor covers, if it reaches that far.
+    fn coverage(&self, y: i64) -> Option<Interval> {
+        let reach = self.radius - (y - self.at.y as i64).abs();
+        let x = self.at.x as i64;
+        (reach >= 0).then(|| Interval::new(x - reach, x + reach))
+    }
+}
+
+fn row_coverage(sensors: &[Sensor], y: i64) -> IntervalSet {
+    sensors.iter()
+        .filter_map(|s| s.coverage(y))
+        .collect()
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    solve_with(input, part, ROW, BOUND)
+}
+
+/// `row` is only used by part 1, & `bound` by part 2.
+pub(crate) fn solve_with(input: Box<dyn Iterator<Item = String>>, part: Part, row: i64, bound: i64) -> String {
+    let sensors: Vec<Sensor> = parse::lines(input, Sensor::parse)
+        .map(|s| s.unwrap_or_else(|e| panic!("{}", e)))
+        .collect();
+
+    let total = match part {
+        Part::Part1 => count_beaconless(&sensors, row),
+        Part::Part2 => find_distress_beacon(&sensors, bound),
+    };
+    format!("{}", total)
+}
+
+// part 1 calculations
+fn count_beaconless(sensors: &[Sensor], row: i64) -> i64 {
+    let covered = row_coverage(sensors, row);
+
+    let beacons_on_row: HashSet<Coord> = sensors.iter()
+        .map(|s| s.beacon)
+        .filter(|b| b.y as i64 == row && covered.contains(b.x as i64))
+        .collect();
+
+    covered.len() as i64 - beacons_on_row.len() as i64
+}
+
+// part 2 calculations
+fn find_distress_beacon(sensors: &[Sensor], bound: i64) -> i64 {
+    let within = Interval::new(0, bound);
+
+    let (x, y) = (0..=bound)
+        .find_map(|y| {
+            let gaps = row_coverage(sensors, y).gaps(within);
+            match gaps.intervals() {
+                [] => None,
+                [gap] if gap.size() == 1 => Some((gap.start, y)),
+                _ => panic!("row {} has more than one uncovered position: {}", y, gaps),
+            }
+        })
+        .expect("there should be one position no sensor covers");
+
+    x * 4_000_000 + y
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    parse::validate_lines(input, Sensor::parse)
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = r"Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve_with(Box::new(lines.clone()), Part::Part1, 10, 20);
+    assert_eq!(output.as_str(), "26");
+
+    let output = solve_with(Box::new(lines), Part::Part2, 10, 20);
+    assert_eq!(output.as_str(), "56000011");
+}
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &[
+        "Sensor at x=2, y=18: closest beacon is at x=-2, y=15",
+        "Sensor at x=2 y=18: closest beacon is at x=-2, y=15",
+        "Sensor at x=2, y=18: closest beacon is at x=-2, y=15.",
+    ], &[2, 3]);
+}
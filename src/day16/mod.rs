@@ -0,0 +1,328 @@
+
+use std::collections::HashMap;
+
+use crate::Part;
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
+
+/// where we (and the elephant) start.
+const START: &str = "AA";
+/// the most valves with a non-zero flow rate we can track, as bits of an opened set.
+const MAX_USEFUL_VALVES: usize = 16;
+
+struct Valve {
+    name: String,
+    flow: u32,
+    tunnels: Vec<String>,
+}
+
+impl Valve {
+    /// two capital letters, ie: `AA`
+    fn parse_name(c: &mut Cursor) -> ParseResult<String> {
+        let start = c.clone();
+        let name: String = [c.char(), c.char()].into_iter()
+            .collect::<ParseResult<String>>()
+            .ok()
+            .filter(|n| n.chars().all(|ch| ch.is_ascii_uppercase()))
+            .ok_or_else(|| start.error(format!("expected a valve name, found '{}'", start.rest())))?;
+        Ok(name)
+    }
+
+    /// `Valve BB has flow rate=13; tunnels lead to valves CC, AA`
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        c.literal("Valve ")?;
+        let name = Valve::parse_name(c)?;
+        c.literal(" has flow rate=")?;
+        let flow = c.integer()?;
+        c.one_of(&[("; tunnels lead to valves ", ()), ("; tunnel leads to valve ", ())])?;
+        let tunnels = c.separated(", ", Valve::parse_name)?;
+
+        Ok(Self { name, flow, tunnels })
+    }
+}
+
+/// opening `valve` (an index into `Network::names`) during `minute`.
+#[derive(Debug, Clone, Copy)]
+struct Opening {
+    minute: u32,
+    valve: usize,
+    released: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Plan {
+    released: u32,
+    schedule: Vec<Opening>,
+}
+
+/// What `Network::explore` has found so far.
+struct Search {
+    minutes: u32,
+    /// the best plan for opening each set of valves (by bitmask).
+    best: Vec<Option<Plan>>,
+    /// the last (time left, pressure released) seen at each opened set & valve.
+    /// arriving somewhere later with less released can't lead to a better plan.
+    seen: Vec<(u32, u32)>,
+}
+
+/// The valves worth opening, and how far apart they all are.
+struct Network {
+    /// the valves with a non-zero flow rate, followed by the start.
+    names: Vec<String>,
+    flows: Vec<u32>,
+    /// minutes to walk between each pair of valves in `names`.
+    dist: Vec<Vec<u32>>,
+}
+
+impl Network {
+    fn parse(input: Box<dyn Iterator<Item = String>>) -> Self {
+        let valves: Vec<Valve> = parse::lines(input, Valve::parse)
+            .map(|v| v.unwrap_or_else(|e| panic!("{}", e)))
+            .collect();
+
+        let index: HashMap<&str, usize> = valves.iter()
+            .enumerate()
+            .map(|(i, v)| (v.name.as_str(), i))
+            .collect();
+        let start = *index.get(START).unwrap_or_else(|| panic!("there should be a valve {}", START));
+
+        // all-pairs shortest paths (Floyd-Warshall) over the whole graph
+        let n = valves.len();
+        let unreachable = u32::MAX / 2;
+        let mut dist = vec![vec![unreachable; n]; n];
+        for (i, valve) in valves.iter().enumerate() {
+            dist[i][i] = 0;
+            for tunnel in &valve.tunnels {
+                let j = *index.get(tunnel.as_str())
+                    .unwrap_or_else(|| panic!("valve {} has a tunnel to unknown valve {}", valve.name, tunnel));
+                dist[i][j] = 1;
+            }
+        }
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    dist[i][j] = dist[i][j].min(dist[i][k] + dist[k][j]);
+                }
+            }
+        }
+
+        // then keep only the valves worth walking to
+        let useful: Vec<usize> = (0..n)
+            .filter(|i| valves[*i].flow > 0)
+            .chain(std::iter::once(start))
+            .collect();
+        assert!(useful.len() <= MAX_USEFUL_VALVES + 1, "at most {} valves can have a flow rate, found {}", MAX_USEFUL_VALVES, useful.len() - 1);
+
+        Self {
+            names: useful.iter().map(|i| valves[*i].name.clone()).collect(),
+            flows: useful.iter().map(|i| valves[*i].flow).collect(),
+            dist: useful.iter().map(|i| useful.iter().map(|j| dist[*i][*j]).collect()).collect(),
+        }
+    }
+
+    /// how many valves are worth opening (the start is the one after them).
+    fn useful(&self) -> usize {
+        self.names.len() - 1
+    }
+
+    /// the best plan for opening each set of valves (by bitmask) within `minutes`.
+    fn best_plans(&self, minutes: u32) -> Vec<Option<Plan>> {
+        let mut search = Search {
+            minutes,
+            best: vec![None; 1 << self.useful()],
+            seen: vec![(0, 0); (1 << self.useful()) * self.names.len()],
+        };
+        self.explore(&mut search, self.useful(), minutes, 0, &mut Plan::default());
+        search.best
+    }
+
+    fn explore(&self, search: &mut Search, at: usize, time_left: u32, opened: usize, plan: &mut Plan) {
+        let seen = &mut search.seen[opened * self.names.len() + at];
+        if seen.0 >= time_left && seen.1 >= plan.released {
+            return;
+        }
+        *seen = (time_left, plan.released);
+
+        if search.best[opened].as_ref().is_none_or(|b| plan.released > b.released) {
+            search.best[opened] = Some(plan.clone());
+        }
+
+        for next in (0..self.useful()).filter(|v| opened & (1 << v) == 0) {
+            // walk there, then spend a minute opening it
+            let cost = self.dist[at][next] + 1;
+            if cost >= time_left {
+                continue;
+            }
+            let remaining = time_left - cost;
+            let released = self.flows[next] * remaining;
+
+            plan.released += released;
+            plan.schedule.push(Opening { minute: search.minutes - remaining, valve: next, released });
+            self.explore(search, next, remaining, opened | (1 << next), plan);
+            plan.schedule.pop();
+            plan.released -= released;
+        }
+    }
+
+    /// the best plan for each worker, and how much pressure they release between them.
+    fn plan(&self, part: &Part) -> (u32, Vec<Plan>) {
+        match part {
+            Part::Part1 => {
+                let plan = self.best_plans(30).into_iter()
+                    .flatten()
+                    .max_by_key(|p| p.released)
+                    .expect("doing nothing is always a plan");
+                (plan.released, vec![plan])
+            },
+            Part::Part2 => {
+                let best = self.best_plans(26);
+
+                // the best plan that only opens valves within each set (ie: what's left for the elephant)
+                let mut within: Vec<(u32, usize)> = best.iter()
+                    .enumerate()
+                    .map(|(mask, p)| (p.as_ref().map_or(0, |p| p.released), mask))
+                    .collect();
+                for mask in 0..within.len() {
+                    for bit in (0..self.useful()).filter(|b| mask & (1 << b) != 0) {
+                        within[mask] = within[mask].max(within[mask ^ (1 << bit)]);
+                    }
+                }
+
+                let all = (1 << self.useful()) - 1;
+                let (released, ours, theirs) = best.iter()
+                    .enumerate()
+                    .filter_map(|(mask, p)| p.as_ref().map(|p| (mask, p)))
+                    .map(|(mask, p)| {
+                        let (other, theirs) = within[all ^ mask];
+                        (p.released + other, mask, theirs)
+                    })
+                    .max()
+                    .expect("doing nothing is always a plan");
+
+                let plans = [ours, theirs].into_iter()
+                    .map(|mask| best[mask].clone().unwrap_or_default())
+                    .collect();
+                (released, plans)
+            },
+        }
+    }
+
+    fn describe(&self, plan: &Plan) -> String {
+        let steps: Vec<String> = plan.schedule.iter()
+            .map(|o| format!("  minute {}: open {} (flow rate {}), releasing {}", o.minute, self.names[o.valve], self.flows[o.valve], o.released))
+            .collect();
+        steps.join("\n")
+    }
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    let network = Network::parse(input);
+
+    format!("{}", network.plan(&part).0)
+}
+
+/// also lists which valves get opened, and when.
+pub(crate) fn explain(input: Box<dyn Iterator<Item = String>>, part: Part) -> (String, String) {
+    let network = Network::parse(input);
+    let (released, plans) = network.plan(&part);
+
+    let explanation: Vec<String> = ["you", "the elephant"].iter()
+        .zip(plans.iter())
+        .map(|(who, plan)| format!("{}:\n{}", who, network.describe(plan)))
+        .collect();
+
+    (format!("{}", released), explanation.join("\n"))
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut valves: Vec<(usize, Valve)> = Vec::new();
+
+    for (i, line) in input.enumerate() {
+        match parse::parse_line(&line, i + 1, Valve::parse) {
+            Ok(valve) => valves.push((i + 1, valve)),
+            Err(e) => issues.push(e.into()),
+        }
+    }
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (line_no, valve) in &valves {
+        if let Some(first) = seen.insert(valve.name.as_str(), *line_no) {
+            issues.push(Issue::new(*line_no, format!("valve {} was already described on line {}", valve.name, first)));
+        }
+    }
+    for (line_no, valve) in &valves {
+        for tunnel in valve.tunnels.iter().filter(|t| !seen.contains_key(t.as_str())) {
+            issues.push(Issue::new(*line_no, format!("tunnel to unknown valve {}", tunnel)));
+        }
+    }
+    if !valves.is_empty() && !seen.contains_key(START) {
+        issues.push(Issue::new(1, format!("there's no valve {} to start from", START)));
+    }
+
+    let useful = valves.iter().filter(|(_, v)| v.flow > 0).count();
+    if useful > MAX_USEFUL_VALVES {
+        issues.push(Issue::new(1, format!("at most {} valves can have a flow rate, found {}", MAX_USEFUL_VALVES, useful)));
+    }
+
+    issues.sort_by_key(|i| i.line);
+    issues
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = r"Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "1651");
+
+    let output = solve(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "1707");
+}
+
+#[test]
+fn test_explain() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let (answer, schedule) = explain(Box::new(lines), Part::Part1);
+    assert_eq!(answer.as_str(), "1651");
+    // the same order as the puzzle's walkthrough
+    let opened: Vec<&str> = schedule.lines()
+        .filter_map(|l| l.split(" open ").nth(1))
+        .map(|l| &l[..2])
+        .collect();
+    assert_eq!(opened, vec!["DD", "BB", "JJ", "HH", "EE", "CC"]);
+
+    let (answer, schedule) = explain(Box::new(EXAMPLE.split('\n').map(String::from)), Part::Part2);
+    assert_eq!(answer.as_str(), "1707");
+    assert!(schedule.contains("the elephant:\n"));
+    assert_eq!(schedule.matches(" open ").count(), 6);
+}
+
+#[test]
+fn test_validate() {
+    // CC is unknown on line 1, BB is repeated on line 3, & 'cc' isn't a valve name on line 4
+    crate::validate::assert_issue_lines(validate, &[
+        "Valve AA has flow rate=0; tunnels lead to valves BB, CC",
+        "Valve BB has flow rate=13; tunnel leads to valve AA",
+        "Valve BB has flow rate=1; tunnel leads to valve AA",
+        "Valve cc has flow rate=2; tunnels lead to valves AA",
+    ], &[1, 3, 4]);
+}
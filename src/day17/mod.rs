@@ -0,0 +1,285 @@
+
+use crate::Part;
+use crate::cycle;
+use crate::validate::Issue;
+
+const WIDTH: usize = 7;
+/// how many rows `explain` draws from the top of the tower, unless it's asked for more (or less).
+pub(crate) const EXPLAIN_ROWS: usize = 20;
+/// how far down from the top of the tower a snapshot looks. anything deeper is treated the same,
+/// otherwise a column that never fills (ie: with only one jet direction) would never repeat.
+const PROFILE_DEPTH: usize = 64;
+
+/// each rock as rows of column bits (bit `x` is column `x` from the left), bottom row first,
+/// already placed two units in from the left wall.
+const ROCKS: [&[u8]; 5] = [
+    // ####
+    &[0b0111100],
+    // .#.
+    // ###
+    // .#.
+    &[0b0001000, 0b0011100, 0b0001000],
+    // ..#
+    // ..#
+    // ###
+    &[0b0011100, 0b0010000, 0b0010000],
+    // #
+    // #
+    // #
+    // #
+    &[0b0000100, 0b0000100, 0b0000100, 0b0000100],
+    // ##
+    // ##
+    &[0b0001100, 0b0001100],
+];
+const LEFT_WALL: u8 = 0b0000001;
+const RIGHT_WALL: u8 = 1 << (WIDTH - 1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Jet {
+    Left,
+    Right,
+}
+
+impl TryFrom<char> for Jet {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '<' => Ok(Jet::Left),
+            '>' => Ok(Jet::Right),
+            c => Err(c),
+        }
+    }
+}
+
+/// What decides how the tower grows from here:
+/// the next jet & rock, and how far down each column is open from the top (up to `PROFILE_DEPTH`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Snapshot {
+    jet: usize,
+    rock: usize,
+    profile: [usize; WIDTH],
+}
+
+struct Chamber {
+    jets: Vec<Jet>,
+    /// settled rock, as column bits per row from the floor up.
+    rows: Vec<u8>,
+    jet: usize,
+    rock: usize,
+    dropped: u64,
+}
+
+impl Chamber {
+    fn parse(mut input: Box<dyn Iterator<Item = String>>) -> Self {
+        let line = input.find(|l| !l.is_empty()).expect("there should be a jet pattern");
+        let jets: Vec<Jet> = line.chars()
+            .map(|c| Jet::try_from(c).unwrap_or_else(|c| panic!("jets should be '<' or '>', found '{}'", c)))
+            .collect();
+
+        Self { jets, rows: Vec::new(), jet: 0, rock: 0, dropped: 0 }
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn collides(&self, rock: &[u8], y: usize) -> bool {
+        rock.iter()
+            .enumerate()
+            .any(|(i, row)| self.rows.get(y + i).is_some_and(|settled| settled & row != 0))
+    }
+
+    /// drops the next rock until it settles, returning how much taller the tower got.
+    fn drop_rock(&mut self) -> usize {
+        let before = self.height();
+        let mut rock: Vec<u8> = ROCKS[self.rock].to_vec();
+        self.rock = (self.rock + 1) % ROCKS.len();
+        let mut y = self.height() + 3;
+
+        loop {
+            let jet = self.jets[self.jet];
+            self.jet = (self.jet + 1) % self.jets.len();
+
+            let pushed: Option<Vec<u8>> = match jet {
+                Jet::Left if rock.iter().all(|r| r & LEFT_WALL == 0) => Some(rock.iter().map(|r| r >> 1).collect()),
+                Jet::Right if rock.iter().all(|r| r & RIGHT_WALL == 0) => Some(rock.iter().map(|r| r << 1).collect()),
+                _ => None,
+            };
+            if let Some(pushed) = pushed.filter(|p| !self.collides(p, y)) {
+                rock = pushed;
+            }
+
+            if y == 0 || self.collides(&rock, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+
+        for (i, row) in rock.iter().enumerate() {
+            match self.rows.get_mut(y + i) {
+                Some(settled) => *settled |= row,
+                None => self.rows.push(*row),
+            }
+        }
+        self.dropped += 1;
+
+        self.height() - before
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let mut profile = [PROFILE_DEPTH; WIDTH];
+        for (x, depth) in profile.iter_mut().enumerate() {
+            if let Some(d) = self.rows.iter().rev().take(PROFILE_DEPTH).position(|row| row & (1 << x) != 0) {
+                *depth = d;
+            }
+        }
+
+        Snapshot { jet: self.jet, rock: self.rock, profile }
+    }
+
+    /// the tower height after `rocks` have fallen, skipping ahead once the tower starts repeating.
+    fn tower_height(&mut self, rocks: u64) -> usize {
+        let initial = self.snapshot();
+
+        // the chamber is the real state, and each snapshot just fingerprints it
+        cycle::extrapolate(
+            initial,
+            rocks,
+            |_| {
+                let grew = self.drop_rock();
+                (self.snapshot(), grew)
+            },
+            |snapshot| snapshot.clone(),
+        )
+    }
+
+    /// draws the top `n` rows of the tower, like the puzzle text does.
+    fn render(&self, n: usize) -> String {
+        let mut lines: Vec<String> = self.rows.iter()
+            .rev()
+            .take(n)
+            .map(|row| {
+                let cells: String = (0..WIDTH)
+                    .map(|x| if row & (1 << x) != 0 { '#' } else { '.' })
+                    .collect();
+                format!("|{}|", cells)
+            })
+            .collect();
+
+        if n >= self.height() {
+            lines.push(format!("+{}+", "-".repeat(WIDTH)));
+        }
+        lines.join("\n")
+    }
+}
+
+fn rocks_for(part: &Part) -> u64 {
+    match part {
+        Part::Part1 => 2022,
+        Part::Part2 => 1_000_000_000_000,
+    }
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    let mut chamber = Chamber::parse(input);
+
+    format!("{}", chamber.tower_height(rocks_for(&part)))
+}
+
+/// also draws the top `rows` of the tower (as far as it was actually simulated).
+pub(crate) fn explain(input: Box<dyn Iterator<Item = String>>, part: Part, rows: usize) -> (String, String) {
+    let mut chamber = Chamber::parse(input);
+    let rocks = rocks_for(&part);
+    let height = chamber.tower_height(rocks);
+
+    let mut explanation = chamber.render(rows);
+    if chamber.dropped < rocks {
+        explanation += &format!("\n\nsimulated {} of {} rocks before the tower started repeating", chamber.dropped, rocks);
+    }
+
+    (format!("{}", height), explanation)
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut patterns = 0;
+
+    for (i, line) in input.enumerate() {
+        let line_no = i + 1;
+        if line.is_empty() {
+            continue;
+        }
+
+        patterns += 1;
+        if patterns > 1 {
+            issues.push(Issue::new(line_no, "there should only be one jet pattern"));
+        }
+        if let Some((col, c)) = line.chars().enumerate().find(|(_, c)| Jet::try_from(*c).is_err()) {
+            issues.push(Issue::new(line_no, format!("jets should be '<' or '>', found '{}' at column {}", c, col + 1)));
+        }
+    }
+
+    if patterns == 0 {
+        issues.push(Issue::new(1, "there's no jet pattern"));
+    }
+    issues
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = r">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "3068");
+
+    let output = solve(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "1514285714288");
+}
+
+#[test]
+fn test_render() {
+    let mut chamber = Chamber::parse(Box::new(EXAMPLE.split('\n').map(String::from)));
+    for _ in 0..3 {
+        chamber.drop_rock();
+    }
+
+    // as drawn in the puzzle's walkthrough after the third rock
+    assert_eq!(chamber.render(10), r"|..#....|
+|..#....|
+|####...|
+|..###..|
+|...#...|
+|..####.|
++-------+");
+    assert_eq!(chamber.render(2), "|..#....|\n|..#....|");
+}
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &[">><<>", "", "<<x>", ""], &[3, 3]);
+}
+
+
+#[test]
+// with a single jet direction the left (or right) columns never fill,
+// which shouldn't stop the tower from being seen to repeat
+fn test_one_jet() {
+    let one_jet = |jet: &str| Box::new(std::iter::once(jet.to_string()));
+
+    // every 5 rocks stack up the same way against the wall
+    assert_eq!(solve(one_jet(">"), Part::Part1), "5256");
+    assert_eq!(solve(one_jet(">"), Part::Part2), "2600000000000");
+    assert_eq!(solve(one_jet("<"), Part::Part2), "2200000000000");
+
+    let (_, explanation) = explain(one_jet(">"), Part::Part2, 3);
+    assert!(explanation.starts_with("|...####|\n|.....##|\n|.....##|\n\nsimulated"), "{}", explanation);
+}
@@ -0,0 +1,123 @@
+
+use std::collections::HashSet;
+
+use crate::Part;
+use crate::grid::Coord3;
+use crate::parse::{self, Cursor, ParseResult};
+use crate::search;
+use crate::validate::Issue;
+
+/// `x,y,z`
+fn parse_cube(c: &mut Cursor) -> ParseResult<Coord3> {
+    let x = c.integer()?;
+    c.literal(",")?;
+    let y = c.integer()?;
+    c.literal(",")?;
+    let z = c.integer()?;
+    Ok(Coord3::new(x, y, z))
+}
+
+/// the corners of the smallest box around all the `cubes`, grown by one in every direction.
+fn bounds_around(cubes: &HashSet<Coord3>) -> (Coord3, Coord3) {
+    let min = |axis: fn(&Coord3) -> isize| cubes.iter().map(axis).min().unwrap_or(0) - 1;
+    let max = |axis: fn(&Coord3) -> isize| cubes.iter().map(axis).max().unwrap_or(0) + 1;
+
+    (
+        Coord3::new(min(|c| c.x), min(|c| c.y), min(|c| c.z)),
+        Coord3::new(max(|c| c.x), max(|c| c.y), max(|c| c.z)),
+    )
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    let cubes: HashSet<Coord3> = parse::lines(input, parse_cube)
+        .map(|c| c.unwrap_or_else(|e| panic!("{}", e)))
+        .collect();
+
+    let total = match part {
+        Part::Part1 => count_exposed_faces(&cubes),
+        Part::Part2 => count_exterior_faces(&cubes),
+    };
+    format!("{}", total)
+}
+
+// part 1 calculations
+fn count_exposed_faces(cubes: &HashSet<Coord3>) -> usize {
+    cubes.iter()
+        .flat_map(|c| c.neighbours6())
+        .filter(|n| !cubes.contains(n))
+        .count()
+}
+
+// part 2 calculations
+fn count_exterior_faces(cubes: &HashSet<Coord3>) -> usize {
+    let (min, max) = bounds_around(cubes);
+    let in_bounds = |c: &Coord3| (min.x..=max.x).contains(&c.x) && (min.y..=max.y).contains(&c.y) && (min.z..=max.z).contains(&c.z);
+
+    // the box leaves a gap all around the droplet, so steam from its corner reaches every outside face
+    let steam = search::reachable([min], |c| {
+        c.neighbours6()
+            .filter(|n| in_bounds(n) && !cubes.contains(n))
+            .collect::<Vec<_>>()
+    });
+
+    cubes.iter()
+        .flat_map(|c| c.neighbours6())
+        .filter(|n| steam.contains(n))
+        .count()
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut seen: HashSet<Coord3> = HashSet::new();
+
+    for (i, line) in input.enumerate() {
+        match parse::parse_line(&line, i + 1, parse_cube) {
+            Ok(cube) if !seen.insert(cube) => issues.push(Issue::new(i + 1, format!("cube {} is listed more than once", cube))),
+            Ok(_) => {},
+            Err(e) => issues.push(e.into()),
+        }
+    }
+
+    issues
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = r"2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "64");
+
+    let output = solve(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "58");
+}
+
+#[test]
+fn test_two_cubes() {
+    let lines = ["1,1,1", "2,1,1"].into_iter().map(String::from);
+    assert_eq!(solve(Box::new(lines), Part::Part1).as_str(), "10");
+}
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["1,1,1", "1,1", "2,1,1", "1,1,1", "1,x,1"], &[2, 4, 5]);
+}
@@ -0,0 +1,286 @@
+
+use std::thread;
+
+use crate::Part;
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mineral {
+    Ore,
+    Clay,
+    Obsidian,
+    Geode,
+}
+
+impl Mineral {
+    const ALL: [Mineral; 4] = [Mineral::Ore, Mineral::Clay, Mineral::Obsidian, Mineral::Geode];
+    const NAMES: [(&'static str, Mineral); 4] = [
+        ("ore", Mineral::Ore),
+        ("clay", Mineral::Clay),
+        ("obsidian", Mineral::Obsidian),
+        ("geode", Mineral::Geode),
+    ];
+
+    fn name(&self) -> &'static str {
+        Mineral::NAMES[*self as usize].0
+    }
+}
+
+/// an amount of each mineral (or robots collecting each), indexed by `Mineral`.
+type Minerals = [u32; 4];
+
+struct Blueprint {
+    id: u32,
+    /// what a robot collecting each mineral costs.
+    costs: [Minerals; 4],
+}
+
+impl Blueprint {
+    /// `Each obsidian robot costs 3 ore and 14 clay.`
+    fn parse_robot(c: &mut Cursor) -> ParseResult<(Mineral, Minerals)> {
+        c.literal("Each ")?;
+        let robot = c.one_of(&Mineral::NAMES)?;
+        c.literal(" robot costs ")?;
+
+        let mut cost = [0; 4];
+        for (amount, mineral) in c.separated(" and ", |c| {
+            let amount: u32 = c.integer()?;
+            c.literal(" ")?;
+            Ok((amount, c.one_of(&Mineral::NAMES)?))
+        })? {
+            cost[mineral as usize] += amount;
+        }
+        c.literal(".")?;
+
+        Ok((robot, cost))
+    }
+
+    /// `Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. ...`
+    fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        c.literal("Blueprint ")?;
+        let id = c.integer()?;
+        c.literal(":")?;
+
+        let mut costs: [Option<Minerals>; 4] = [None; 4];
+        while c.whitespace() > 0 {
+            let start = c.clone();
+            let (robot, cost) = Blueprint::parse_robot(c)?;
+            if costs[robot as usize].replace(cost).is_some() {
+                return Err(start.error(format!("the {} robot's cost was already given", robot.name())));
+            }
+        }
+
+        match Mineral::ALL.into_iter().find(|m| costs[*m as usize].is_none()) {
+            Some(missing) => Err(c.error(format!("missing the cost of a {} robot", missing.name()))),
+            None => Ok(Self { id, costs: costs.map(Option::unwrap_or_default) }),
+        }
+    }
+
+    /// there's no point collecting more of a mineral per minute than the factory can spend.
+    fn max_useful_robots(&self) -> Minerals {
+        let mut max = [u32::MAX; 4];
+        for mineral in [Mineral::Ore, Mineral::Clay, Mineral::Obsidian] {
+            max[mineral as usize] = self.costs.iter().map(|cost| cost[mineral as usize]).max().unwrap_or(0);
+        }
+        max
+    }
+}
+
+/// building a robot collecting `robot`, finishing at the end of `minute`.
+#[derive(Debug, Clone, Copy)]
+struct Build {
+    minute: u32,
+    robot: Mineral,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Plan {
+    geodes: u32,
+    builds: Vec<Build>,
+}
+
+#[derive(Clone, Copy)]
+struct State {
+    time_left: u32,
+    robots: Minerals,
+    stock: Minerals,
+}
+
+/// a depth-first search over which robot to build next (waiting as long as needed for it).
+struct Search<'a> {
+    blueprint: &'a Blueprint,
+    minutes: u32,
+    max_robots: Minerals,
+    best: Plan,
+}
+
+impl Search<'_> {
+    fn explore(&mut self, state: State, builds: &mut Vec<Build>) {
+        let geode = Mineral::Geode as usize;
+
+        // just waiting out the clock
+        let geodes = state.stock[geode] + state.robots[geode] * state.time_left;
+        if geodes > self.best.geodes {
+            self.best = Plan { geodes, builds: builds.clone() };
+        }
+
+        // even building a geode robot every minute from now on couldn't beat the best so far
+        let t = state.time_left;
+        if geodes + t * t.saturating_sub(1) / 2 <= self.best.geodes {
+            return;
+        }
+
+        // geode robots first, as they're the most likely to lead to a good plan early
+        for robot in Mineral::ALL.into_iter().rev() {
+            if state.robots[robot as usize] >= self.max_robots[robot as usize] {
+                continue;
+            }
+            let Some(wait) = self.wait_for(&state, robot) else {
+                continue;
+            };
+            // a robot built in the last minute never collects anything
+            if wait + 1 >= state.time_left {
+                continue;
+            }
+
+            let cost = self.blueprint.costs[robot as usize];
+            let mut next = State {
+                time_left: state.time_left - (wait + 1),
+                robots: state.robots,
+                stock: std::array::from_fn(|m| state.stock[m] + state.robots[m] * (wait + 1) - cost[m]),
+            };
+            next.robots[robot as usize] += 1;
+
+            builds.push(Build { minute: self.minutes - next.time_left, robot });
+            self.explore(next, builds);
+            builds.pop();
+        }
+    }
+
+    /// minutes to wait before `robot` can be built, if the robots we have can ever collect enough.
+    fn wait_for(&self, state: &State, robot: Mineral) -> Option<u32> {
+        let cost = self.blueprint.costs[robot as usize];
+
+        (0..4).map(|m| {
+            if cost[m] <= state.stock[m] {
+                Some(0)
+            } else if state.robots[m] == 0 {
+                None
+            } else {
+                Some((cost[m] - state.stock[m]).div_ceil(state.robots[m]))
+            }
+        })
+            .try_fold(0, |wait, w| w.map(|w| wait.max(w)))
+    }
+}
+
+/// the plan opening the most geodes within `minutes`.
+fn best_plan(blueprint: &Blueprint, minutes: u32) -> Plan {
+    let mut search = Search { blueprint, minutes, max_robots: blueprint.max_useful_robots(), best: Plan::default() };
+
+    let start = State { time_left: minutes, robots: [1, 0, 0, 0], stock: [0; 4] };
+    search.explore(start, &mut Vec::new());
+    search.best
+}
+
+/// the best plan for each blueprint that gets used, each searched on its own thread.
+fn best_plans(input: Box<dyn Iterator<Item = String>>, part: &Part) -> Vec<(Blueprint, Plan)> {
+    let blueprints: Vec<Blueprint> = parse::lines(input, Blueprint::parse)
+        .map(|b| b.unwrap_or_else(|e| panic!("{}", e)))
+        .collect();
+
+    let (minutes, used) = match part {
+        Part::Part1 => (24, blueprints.len()),
+        Part::Part2 => (32, 3),
+    };
+
+    let plans: Vec<Plan> = thread::scope(|s| {
+        let handles: Vec<_> = blueprints.iter()
+            .take(used)
+            .map(|blueprint| s.spawn(move || best_plan(blueprint, minutes)))
+            .collect();
+
+        handles.into_iter()
+            .map(|h| h.join().expect("the search shouldn't panic"))
+            .collect()
+    });
+
+    blueprints.into_iter().zip(plans).collect()
+}
+
+fn total(plans: &[(Blueprint, Plan)], part: &Part) -> u32 {
+    match part {
+        Part::Part1 => plans.iter().map(|(blueprint, plan)| blueprint.id * plan.geodes).sum(),
+        Part::Part2 => plans.iter().map(|(_, plan)| plan.geodes).product(),
+    }
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    let plans = best_plans(input, &part);
+
+    format!("{}", total(&plans, &part))
+}
+
+/// also lists the build order for each blueprint.
+pub(crate) fn explain(input: Box<dyn Iterator<Item = String>>, part: Part) -> (String, String) {
+    let plans = best_plans(input, &part);
+
+    let explanation: Vec<String> = plans.iter()
+        .map(|(blueprint, plan)| {
+            let builds: Vec<String> = plan.builds.iter()
+                .map(|b| format!("  minute {}: {} robot", b.minute, b.robot.name()))
+                .collect();
+            format!("blueprint {} opens {} geode(s):\n{}", blueprint.id, plan.geodes, builds.join("\n"))
+        })
+        .collect();
+
+    (format!("{}", total(&plans, &part)), explanation.join("\n"))
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    parse::validate_lines(input, Blueprint::parse)
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = r"Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "33");
+
+    // only 2 blueprints in the example, so just check them one by one
+    let blueprints: Vec<Blueprint> = parse::lines(lines, Blueprint::parse).map(Result::unwrap).collect();
+    assert_eq!(best_plan(&blueprints[0], 32).geodes, 56);
+    assert_eq!(best_plan(&blueprints[1], 32).geodes, 62);
+}
+
+#[test]
+fn test_explain() {
+    let lines = EXAMPLE.split('\n')
+        .take(1)
+        .map(String::from);
+
+    let (answer, builds) = explain(Box::new(lines), Part::Part1);
+    assert_eq!(answer.as_str(), "9");
+    assert!(builds.starts_with("blueprint 1 opens 9 geode(s):\n"));
+    assert_eq!(builds.matches(": geode robot").count(), 2);
+}
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &[
+        "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.",
+        "Blueprint 2: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay.",
+        "Blueprint 3: Each ore robot costs 4 ore. Each ore robot costs 2 ore.",
+        "Blueprint 4: Each ore robot costs 4 gold.",
+    ], &[2, 3, 4]);
+}
@@ -0,0 +1,183 @@
+
+use crate::Part;
+use crate::parse;
+use crate::validate::Issue;
+
+const DECRYPTION_KEY: i64 = 811_589_153;
+/// the grove coordinates are these many numbers after the 0.
+const COORDINATE_OFFSETS: [usize; 3] = [1000, 2000, 3000];
+
+/// The numbers being mixed (by their index in the original list), kept as a run of
+/// small buckets so moving a number only shifts the bucket it leaves & the one it joins.
+struct Ring {
+    buckets: Vec<Vec<usize>>,
+    /// which bucket each number is in.
+    bucket_of: Vec<usize>,
+    /// how big buckets start out.
+    bucket_size: usize,
+}
+
+impl Ring {
+    fn new(len: usize) -> Self {
+        let bucket_size = (len as f64).sqrt().ceil().max(1.0) as usize;
+        let mut ring = Self { buckets: vec![(0..len).collect()], bucket_of: vec![0; len], bucket_size };
+        ring.rebalance();
+        ring
+    }
+
+    /// splits the numbers back into evenly sized buckets, keeping their order.
+    fn rebalance(&mut self) {
+        let order: Vec<usize> = self.iter().collect();
+        self.buckets = order.chunks(self.bucket_size).map(|c| c.to_vec()).collect();
+
+        for (b, bucket) in self.buckets.iter().enumerate() {
+            for item in bucket {
+                self.bucket_of[*item] = b;
+            }
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.buckets.iter().flatten().copied()
+    }
+
+    /// takes `item` out of the ring, returning where it was.
+    fn remove(&mut self, item: usize) -> usize {
+        let b = self.bucket_of[item];
+        let offset = self.buckets[b].iter().position(|i| *i == item).expect("items are in their bucket");
+        self.buckets[b].remove(offset);
+
+        self.buckets[..b].iter().map(|bucket| bucket.len()).sum::<usize>() + offset
+    }
+
+    /// puts `item` back so it's at `position` (counting from the start of the ring).
+    fn insert(&mut self, mut position: usize, item: usize) {
+        let last = self.buckets.len() - 1;
+        let mut b = 0;
+        while b < last && position > self.buckets[b].len() {
+            position -= self.buckets[b].len();
+            b += 1;
+        }
+
+        self.buckets[b].insert(position, item);
+        self.bucket_of[item] = b;
+
+        if self.buckets[b].len() > 2 * self.bucket_size {
+            self.rebalance();
+        }
+    }
+}
+
+fn mix(numbers: &[i64], rounds: usize) -> Vec<i64> {
+    let mut ring = Ring::new(numbers.len());
+    // a number moving all the way round (past the other len - 1) ends up where it started
+    let cycle = numbers.len() as i64 - 1;
+
+    for _ in 0..rounds {
+        for (item, n) in numbers.iter().enumerate() {
+            let from = ring.remove(item);
+            let to = (from as i64 + n).rem_euclid(cycle.max(1));
+            ring.insert(to as usize, item);
+        }
+    }
+
+    ring.iter().map(|item| numbers[item]).collect()
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    let numbers: Vec<i64> = parse::lines(input, |c| c.integer())
+        .map(|n| n.unwrap_or_else(|e| panic!("{}", e)))
+        .collect();
+
+    let (key, rounds) = match part {
+        Part::Part1 => (1, 1),
+        Part::Part2 => (DECRYPTION_KEY, 10),
+    };
+    let decrypted: Vec<i64> = numbers.iter().map(|n| n * key).collect();
+
+    format!("{}", sum_grove_coordinates(&mix(&decrypted, rounds)))
+}
+
+fn sum_grove_coordinates(mixed: &[i64]) -> i64 {
+    let zero = mixed.iter().position(|n| *n == 0).expect("there should be a 0");
+
+    COORDINATE_OFFSETS.iter()
+        .map(|offset| mixed[(zero + offset) % mixed.len()])
+        .sum()
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut zeros: Vec<usize> = Vec::new();
+
+    for (i, line) in input.enumerate() {
+        match parse::parse_line(&line, i + 1, |c| c.integer::<i64>()) {
+            Ok(0) => zeros.push(i + 1),
+            Ok(n) if n.checked_mul(DECRYPTION_KEY).is_none() => {
+                issues.push(Issue::new(i + 1, format!("{} is too big to decrypt", n)));
+            },
+            Ok(_) => {},
+            Err(e) => issues.push(e.into()),
+        }
+    }
+
+    match zeros.as_slice() {
+        [] => issues.push(Issue::new(1, "there should be a 0")),
+        [_] => {},
+        [_, repeats @ ..] => issues.extend(repeats.iter().map(|line| Issue::new(*line, "there should only be one 0"))),
+    }
+
+    issues.sort_by_key(|i| i.line);
+    issues
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = r"1
+2
+-3
+3
+-2
+0
+4";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "3");
+
+    let output = solve(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "1623178306");
+}
+
+#[test]
+fn test_mix_vs_naive() {
+    // duplicates, numbers bigger than the list, & enough of them to need several buckets
+    let numbers: Vec<i64> = (0..200_i64)
+        .map(|i| (i * 7919 % 61) - 30)
+        .chain([0, 5, 5, -5, 1000, -1000])
+        .collect();
+
+    let mut naive: Vec<usize> = (0..numbers.len()).collect();
+    for _ in 0..3 {
+        for (item, n) in numbers.iter().enumerate() {
+            let from = naive.iter().position(|i| *i == item).unwrap();
+            naive.remove(from);
+            let to = (from as i64 + n).rem_euclid(naive.len() as i64);
+            naive.insert(to as usize, item);
+        }
+    }
+    let naive: Vec<i64> = naive.into_iter().map(|i| numbers[i]).collect();
+
+    assert_eq!(mix(&numbers, 3), naive);
+}
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["1", "0", "x", "0", "99999999999999"], &[3, 4, 5]);
+}
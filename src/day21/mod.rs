@@ -0,0 +1,271 @@
+
+use std::collections::HashMap;
+
+use crate::Part;
+use crate::math::{Operand, Rational};
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
+
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
+
+type MonkeyIndex = usize;
+
+enum Job {
+    Number(i64),
+    Operation { left: MonkeyIndex, op: Operand, right: MonkeyIndex },
+}
+
+/// a job as written, before monkey names are resolved.
+enum RawJob<'a> {
+    Number(i64),
+    Operation { left: &'a str, op: Operand, right: &'a str },
+}
+
+/// a monkey's name, ie: `root`
+fn parse_name<'a>(c: &mut Cursor<'a>) -> ParseResult<&'a str> {
+    let rest = c.rest();
+    let len = rest.bytes().take_while(u8::is_ascii_lowercase).count();
+    if len == 0 {
+        return Err(c.error(format!("expected a monkey's name, found '{}'", rest)));
+    }
+
+    let name = &rest[..len];
+    c.literal(name)?;
+    Ok(name)
+}
+
+/// `root: pppw + sjmn` or `dbpl: 5`
+fn parse_monkey<'a>(c: &mut Cursor<'a>) -> ParseResult<(&'a str, RawJob<'a>)> {
+    let name = parse_name(c)?;
+    c.literal(": ")?;
+
+    if let Some(n) = c.attempt(|c| c.integer()) {
+        return Ok((name, RawJob::Number(n)));
+    }
+    let left = parse_name(c)?;
+    c.literal(" ")?;
+    let op = Operand::parse(c)?;
+    c.literal(" ")?;
+    let right = parse_name(c)?;
+
+    Ok((name, RawJob::Operation { left, op, right }))
+}
+
+/// The monkeys' jobs, as a DAG where an operation points at the two monkeys it waits on.
+struct Troop {
+    names: Vec<String>,
+    jobs: Vec<Job>,
+    index: HashMap<String, MonkeyIndex>,
+}
+
+impl Troop {
+    fn parse(input: Box<dyn Iterator<Item = String>>) -> Self {
+        let lines: Vec<(usize, String)> = input.enumerate()
+            .filter(|(_, l)| !l.is_empty())
+            .collect();
+
+        let monkeys: Vec<(&str, RawJob)> = lines.iter()
+            .map(|(i, line)| parse::parse_line(line, i + 1, parse_monkey).unwrap_or_else(|e| panic!("{}", e)))
+            .collect();
+
+        let index: HashMap<String, MonkeyIndex> = monkeys.iter()
+            .enumerate()
+            .map(|(i, (name, _))| (name.to_string(), i))
+            .collect();
+        let find = |name: &str| *index.get(name).unwrap_or_else(|| panic!("there's no monkey called '{}'", name));
+
+        let jobs = monkeys.iter()
+            .map(|(_, job)| match job {
+                RawJob::Number(n) => Job::Number(*n),
+                RawJob::Operation { left, op, right } => Job::Operation { left: find(left), op: *op, right: find(right) },
+            })
+            .collect();
+
+        Self { names: monkeys.iter().map(|(name, _)| name.to_string()).collect(), jobs, index }
+    }
+
+    fn find(&self, name: &str) -> MonkeyIndex {
+        *self.index.get(name).unwrap_or_else(|| panic!("there's no monkey called '{}'", name))
+    }
+
+    /// what each monkey yells (monkeys waiting on more than one other are shared, so each is only worked out once).
+    fn yells(&self) -> Vec<Rational> {
+        let mut yelled: Vec<Option<Rational>> = vec![None; self.jobs.len()];
+        let mut waiting = vec![false; self.jobs.len()];
+        for monkey in 0..self.jobs.len() {
+            self.yell(monkey, &mut yelled, &mut waiting);
+        }
+        yelled.into_iter().map(|y| y.expect("every monkey has yelled")).collect()
+    }
+
+    fn yell(&self, monkey: MonkeyIndex, yelled: &mut [Option<Rational>], waiting: &mut [bool]) -> Rational {
+        if let Some(value) = yelled[monkey] {
+            return value;
+        }
+        assert!(!waiting[monkey], "monkey '{}' ends up waiting on itself", self.names[monkey]);
+        waiting[monkey] = true;
+
+        let value = match self.jobs[monkey] {
+            Job::Number(n) => Rational::from(n),
+            Job::Operation { left, op, right } => {
+                let (left, right) = (self.yell(left, yelled, waiting), self.yell(right, yelled, waiting));
+                op.do_op(left, right).unwrap_or_else(|e| panic!("monkey '{}': {}", self.names[monkey], e))
+            },
+        };
+
+        waiting[monkey] = false;
+        yelled[monkey] = Some(value);
+        value
+    }
+
+    /// the monkeys from `root` down to `humn` (inclusive), if `humn` is one `root` waits on.
+    fn path_to_human(&self) -> Vec<MonkeyIndex> {
+        let (root, human) = (self.find(ROOT), self.find(HUMAN));
+
+        let mut path = Vec::new();
+        let mut stack = vec![(root, 0)];
+        while let Some((monkey, depth)) = stack.pop() {
+            path.truncate(depth);
+            path.push(monkey);
+            if monkey == human {
+                return path;
+            }
+            if let Job::Operation { left, right, .. } = self.jobs[monkey] {
+                stack.extend([(left, depth + 1), (right, depth + 1)]);
+            }
+        }
+        panic!("'{}' doesn't wait on '{}'", ROOT, HUMAN)
+    }
+
+    /// what `humn` has to yell for both sides of `root` to be equal.
+    fn solve_for_human(&self) -> Rational {
+        let yelled = self.yells();
+        let path = self.path_to_human();
+
+        let Job::Operation { left, right, .. } = self.jobs[path[0]] else {
+            panic!("'{}' should compare two monkeys", ROOT);
+        };
+        // the side without the human has to be matched by the side with it
+        let mut target = if path[1] == left { yelled[right] } else { yelled[left] };
+
+        // then undo each operation on the way down to the human
+        for step in path[1..].windows(2) {
+            let (monkey, next) = (step[0], step[1]);
+            let Job::Operation { left, op, right } = self.jobs[monkey] else {
+                unreachable!("only operations lead further down");
+            };
+
+            let solved = match (left == next, right == next) {
+                (true, false) => op.solve_left(yelled[right], target),
+                (false, true) => op.solve_right(yelled[left], target),
+                _ => panic!("monkey '{}' depends on '{}' from both sides, so it can't be inverted", self.names[monkey], HUMAN),
+            };
+            target = solved.unwrap_or_else(|e| panic!("monkey '{}': {}", self.names[monkey], e));
+        }
+
+        target
+    }
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    let troop = Troop::parse(input);
+
+    let total = match part {
+        Part::Part1 => troop.yells()[troop.find(ROOT)],
+        Part::Part2 => troop.solve_for_human(),
+    };
+    format!("{}", total)
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let lines: Vec<String> = input.collect();
+
+    let mut monkeys: Vec<(usize, &str, RawJob)> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match parse::parse_line(line, i + 1, parse_monkey) {
+            Ok((name, job)) => monkeys.push((i + 1, name, job)),
+            Err(e) => issues.push(e.into()),
+        }
+    }
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (line_no, name, _) in &monkeys {
+        if let Some(first) = seen.insert(name, *line_no) {
+            issues.push(Issue::new(*line_no, format!("monkey '{}' was already described on line {}", name, first)));
+        }
+    }
+    for (line_no, _, job) in &monkeys {
+        if let RawJob::Operation { left, right, .. } = job {
+            for name in [left, right].into_iter().filter(|n| !seen.contains_key(*n)) {
+                issues.push(Issue::new(*line_no, format!("there's no monkey called '{}'", name)));
+            }
+        }
+    }
+
+    for name in [ROOT, HUMAN] {
+        if !monkeys.is_empty() && !seen.contains_key(name) {
+            issues.push(Issue::new(1, format!("there's no monkey called '{}'", name)));
+        }
+    }
+    if let Some((line_no, _, RawJob::Number(_))) = monkeys.iter().find(|(_, name, _)| *name == ROOT) {
+        issues.push(Issue::new(*line_no, format!("'{}' should compare two monkeys", ROOT)));
+    }
+
+    issues.sort_by_key(|i| i.line);
+    issues
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = r"root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "152");
+
+    let output = solve(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "301");
+}
+
+#[test]
+fn test_exact_division() {
+    // 7 / 2 * 2 would be 6 if the division truncated
+    let lines = ["root: half * two", "half: seven / two", "seven: 7", "two: 2", "humn: 1"]
+        .into_iter()
+        .map(String::from);
+    assert_eq!(solve(Box::new(lines), Part::Part1).as_str(), "7");
+
+    // humn / 3 = 1 / 2
+    let lines = ["root: third + half", "third: humn / three", "half: one / two", "three: 3", "one: 1", "two: 2", "humn: 0"]
+        .into_iter()
+        .map(String::from);
+    assert_eq!(solve(Box::new(lines), Part::Part2).as_str(), "3/2");
+}
+
+#[test]
+fn test_validate() {
+    // 'aaaa' is repeated, 'cccc' & 'humn' don't exist, & 'Humn' isn't a name
+    crate::validate::assert_issue_lines(validate, &["root: aaaa + bbbb", "aaaa: 5", "aaaa: 6", "bbbb: cccc * humn", "Humn: 3"], &[1, 3, 4, 4, 5]);
+}
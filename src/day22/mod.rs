@@ -0,0 +1,413 @@
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::Part;
+use crate::grid::{Coord, Coord3, Direction, Grid};
+use crate::parse::{self, Cursor, ParseResult};
+use crate::validate::Issue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    /// off the board.
+    Void,
+    Open,
+    Wall,
+}
+
+impl TryFrom<char> for Tile {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            ' ' => Ok(Tile::Void),
+            '.' => Ok(Tile::Open),
+            '#' => Ok(Tile::Wall),
+            c => Err(c),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Forward(usize),
+    TurnLeft,
+    TurnRight,
+}
+
+/// `10R5L5R10L4R5L5`
+fn parse_path(c: &mut Cursor) -> ParseResult<Vec<Step>> {
+    let mut steps = Vec::new();
+    while !c.is_at_end() {
+        match c.attempt(|c| c.integer()) {
+            Some(n) => steps.push(Step::Forward(n)),
+            None => steps.push(c.one_of(&[("L", Step::TurnLeft), ("R", Step::TurnRight)])?),
+        }
+    }
+    Ok(steps)
+}
+
+/// the puzzle's score for each facing.
+fn facing_score(d: Direction) -> usize {
+    match d {
+        Direction::Right => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Up => 3,
+    }
+}
+
+struct Board {
+    tiles: Grid<Tile>,
+}
+
+impl Board {
+    /// the board's rows, padded out with void to be as wide as the widest one.
+    fn parse(rows: &[String]) -> Self {
+        let width = rows.iter().map(|r| r.chars().count()).max().unwrap_or(0);
+        let rows: Vec<Vec<Tile>> = rows.iter()
+            .enumerate()
+            .map(|(y, row)| row.chars()
+                .map(|c| Tile::try_from(c).unwrap_or_else(|c| panic!("line {}: tiles should be ' ', '.' or '#', found '{}'", y + 1, c)))
+                .chain(std::iter::repeat(Tile::Void))
+                .take(width)
+                .collect())
+            .collect();
+
+        let tiles = Grid::from_rows(rows).unwrap_or_else(|e| panic!("invalid board: {}", e));
+        Self { tiles }
+    }
+
+    fn is_void(&self, c: Coord) -> bool {
+        self.tiles.get(c).is_none_or(|t| *t == Tile::Void)
+    }
+
+    /// the leftmost open tile of the top row.
+    fn start(&self) -> Coord {
+        self.tiles.find(|t| *t == Tile::Open).expect("the board should have an open tile")
+    }
+
+    /// wrapping around to the far side of the board's row or column.
+    fn wrap_flat(&self, pos: Coord, dir: Direction) -> (Coord, Direction) {
+        let far_side = self.tiles.ray(pos, dir.opposite())
+            .take_while(|(_, t)| **t != Tile::Void)
+            .last()
+            .map_or(pos, |(c, _)| c);
+        (far_side, dir)
+    }
+
+    /// follows `path` from the start, wrapping off the board with `wrap`, returning where it ends & which way it faces.
+    fn walk(&self, path: &[Step], wrap: impl Fn(Coord, Direction) -> (Coord, Direction)) -> (Coord, Direction) {
+        let mut pos = self.start();
+        let mut dir = Direction::Right;
+
+        for step in path {
+            match step {
+                Step::TurnLeft => dir = dir.turn_left(),
+                Step::TurnRight => dir = dir.turn_right(),
+                Step::Forward(n) => {
+                    for _ in 0..*n {
+                        let next = pos.step(dir);
+                        let (next, next_dir) = if self.is_void(next) { wrap(pos, dir) } else { (next, dir) };
+                        if self.tiles[next] == Tile::Wall {
+                            break;
+                        }
+                        (pos, dir) = (next, next_dir);
+                    }
+                },
+            }
+        }
+
+        (pos, dir)
+    }
+}
+
+/// A face of the cube: where it is on the board,
+/// and which way its right, down & outward directions point once folded.
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    /// the face's position in the net, in face sized steps.
+    net: Coord,
+    right: Coord3,
+    down: Coord3,
+    normal: Coord3,
+}
+
+fn negate(v: Coord3) -> Coord3 {
+    Coord3::default() - v
+}
+
+impl Face {
+    /// the 3D direction of moving `dir` on this face.
+    fn towards(&self, dir: Direction) -> Coord3 {
+        match dir {
+            Direction::Right => self.right,
+            Direction::Left => negate(self.right),
+            Direction::Down => self.down,
+            Direction::Up => negate(self.down),
+        }
+    }
+
+    /// the face next to this one in the net, folded down over the edge in `dir`.
+    fn fold(&self, dir: Direction) -> Face {
+        let net = self.net.step(dir);
+        let away = self.towards(dir);
+        match dir {
+            Direction::Right | Direction::Left => Face { net, right: self.right_after_fold(dir), down: self.down, normal: away },
+            Direction::Down | Direction::Up => Face { net, right: self.right, down: self.down_after_fold(dir), normal: away },
+        }
+    }
+
+    // folding over an edge turns the direction crossing it to point back into the cube
+    fn right_after_fold(&self, dir: Direction) -> Coord3 {
+        if dir == Direction::Right { negate(self.normal) } else { self.normal }
+    }
+
+    fn down_after_fold(&self, dir: Direction) -> Coord3 {
+        if dir == Direction::Down { negate(self.normal) } else { self.normal }
+    }
+
+    /// the direction on this face that points along `v`.
+    fn direction_of(&self, v: Coord3) -> Direction {
+        Direction::ALL.into_iter()
+            .find(|d| self.towards(*d) == v)
+            .expect("every direction along a face is one of its 4")
+    }
+}
+
+/// The board folded up into a cube.
+struct Cube {
+    size: isize,
+    faces: Vec<Face>,
+    /// each face by its position in the net.
+    by_net: HashMap<Coord, usize>,
+}
+
+impl Cube {
+    /// folds the net by walking across it from the first face, working out how each face lies from its neighbour.
+    fn fold(board: &Board) -> Self {
+        let area = board.tiles.iter().filter(|(_, t)| **t != Tile::Void).count();
+        let size = ((area / 6) as f64).sqrt().round() as isize;
+        assert!(size > 0 && (size * size * 6) as usize == area, "the board should have 6 square faces, not {} tiles", area);
+
+        let on_board = |net: Coord| !board.is_void(Coord::new(net.x * size, net.y * size));
+        let first = board.start();
+        let first = Face {
+            net: Coord::new(first.x / size, first.y / size),
+            right: Coord3::new(1, 0, 0),
+            down: Coord3::new(0, 1, 0),
+            normal: Coord3::new(0, 0, -1),
+        };
+
+        let mut faces = vec![first];
+        let mut by_net = HashMap::from([(first.net, 0)]);
+        let mut queue = VecDeque::from([first]);
+        while let Some(face) = queue.pop_front() {
+            for dir in Direction::ALL {
+                let next = face.net.step(dir);
+                if next.x < 0 || next.y < 0 || !on_board(next) || by_net.contains_key(&next) {
+                    continue;
+                }
+                let folded = face.fold(dir);
+                by_net.insert(next, faces.len());
+                faces.push(folded);
+                queue.push_back(folded);
+            }
+        }
+
+        assert!(faces.len() == 6, "the board should fold into a cube, but its faces aren't all joined up");
+        let mut normals: Vec<Coord3> = faces.iter().map(|f| f.normal).collect();
+        normals.sort();
+        normals.dedup();
+        assert!(normals.len() == 6, "the board's net doesn't fold into a cube, some of its faces overlap");
+
+        Self { size, faces, by_net }
+    }
+
+    fn face_of(&self, pos: Coord) -> &Face {
+        let net = Coord::new(pos.x.div_euclid(self.size), pos.y.div_euclid(self.size));
+        &self.faces[self.by_net[&net]]
+    }
+
+    /// the cell on the edge of a face at `index` (from the end `along` points away from),
+    /// on the side in `side`.
+    fn edge_cell(&self, face: &Face, side: Direction, along: Direction, index: isize) -> Coord {
+        let last = self.size - 1;
+        let across = match side {
+            Direction::Right | Direction::Down => last,
+            Direction::Left | Direction::Up => 0,
+        };
+        let along_index = match along {
+            Direction::Right | Direction::Down => index,
+            Direction::Left | Direction::Up => last - index,
+        };
+
+        let local = match side {
+            Direction::Left | Direction::Right => Coord::new(across, along_index),
+            Direction::Up | Direction::Down => Coord::new(along_index, across),
+        };
+        Coord::new(face.net.x * self.size, face.net.y * self.size) + local
+    }
+
+    /// wrapping over the edge of the face onto the one it's folded up against.
+    fn wrap(&self, pos: Coord, dir: Direction) -> (Coord, Direction) {
+        let from = self.face_of(pos);
+
+        // the face we're heading onto is the one facing the way we're going
+        let heading = from.towards(dir);
+        let to = self.faces.iter()
+            .find(|f| f.normal == heading)
+            .expect("every direction has a face");
+        // and from its edge, we head back in towards the middle of the cube
+        let new_dir = to.direction_of(negate(from.normal));
+
+        // the shared edge runs the same way in 3D on both faces
+        let along = dir.turn_right();
+        let local = Coord::new(pos.x.rem_euclid(self.size), pos.y.rem_euclid(self.size));
+        let index = match along {
+            Direction::Right => local.x,
+            Direction::Left => self.size - 1 - local.x,
+            Direction::Down => local.y,
+            Direction::Up => self.size - 1 - local.y,
+        };
+        let along_to = to.direction_of(from.towards(along));
+
+        (self.edge_cell(to, new_dir.opposite(), along_to, index), new_dir)
+    }
+}
+
+fn parse_notes(input: Box<dyn Iterator<Item = String>>) -> (Board, Vec<Step>) {
+    let lines: Vec<String> = input.collect();
+    let blank = lines.iter().position(|l| l.trim().is_empty()).unwrap_or(lines.len());
+
+    let board = Board::parse(&lines[..blank]);
+    let (line_no, path) = lines.iter()
+        .enumerate()
+        .skip(blank)
+        .find(|(_, l)| !l.trim().is_empty())
+        .expect("there should be a path after the board");
+    let path = parse::parse_line(path, line_no + 1, parse_path).unwrap_or_else(|e| panic!("{}", e));
+
+    (board, path)
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    let (board, path) = parse_notes(input);
+
+    let (pos, dir) = match part {
+        Part::Part1 => board.walk(&path, |pos, dir| board.wrap_flat(pos, dir)),
+        Part::Part2 => {
+            let cube = Cube::fold(&board);
+            board.walk(&path, |pos, dir| cube.wrap(pos, dir))
+        },
+    };
+
+    let password = 1000 * (pos.y as usize + 1) + 4 * (pos.x as usize + 1) + facing_score(dir);
+    format!("{}", password)
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut lines = input.enumerate();
+    let mut tiles = 0;
+
+    for (i, line) in lines.by_ref() {
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((col, c)) = line.chars().enumerate().find(|(_, c)| Tile::try_from(*c).is_err()) {
+            issues.push(Issue::new(i + 1, format!("tiles should be ' ', '.' or '#', found '{}' at column {}", c, col + 1)));
+        }
+        tiles += line.chars().filter(|c| *c != ' ').count();
+    }
+
+    let size = ((tiles / 6) as f64).sqrt().round() as usize;
+    if tiles == 0 || size * size * 6 != tiles {
+        issues.push(Issue::new(1, format!("the board should have 6 square faces, not {} tiles", tiles)));
+    }
+
+    match lines.find(|(_, l)| !l.trim().is_empty()) {
+        Some((i, path)) => issues.extend(parse::parse_line(&path, i + 1, parse_path).err().map(Issue::from)),
+        None => issues.push(Issue::new(1, "there should be a path after the board")),
+    }
+    if let Some((i, _)) = lines.find(|(_, l)| !l.trim().is_empty()) {
+        issues.push(Issue::new(i + 1, "there should be nothing after the path"));
+    }
+
+    issues.sort_by_key(|i| i.line);
+    issues
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "6032");
+
+    let output = solve(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "5031");
+}
+
+#[cfg(test)]
+/// the board for `net` (a map of which faces are where), with no walls.
+fn open_board(net: &[&str], size: usize) -> Board {
+    let rows: Vec<String> = net.iter()
+        .flat_map(|row| {
+            let line: String = row.chars()
+                .map(|c| if c == '#' { ".".repeat(size) } else { " ".repeat(size) })
+                .collect();
+            std::iter::repeat_n(line, size)
+        })
+        .collect();
+    Board::parse(&rows)
+}
+
+#[test]
+// the real input's layout, which is folded differently from the example
+fn test_cube_edges() {
+    let board = open_board(&[".##", ".#.", "##.", "#.."], 4);
+    let cube = Cube::fold(&board);
+
+    // wrapping over every edge and straight back again ends up where it started
+    for (pos, tile) in board.tiles.iter() {
+        if *tile == Tile::Void {
+            continue;
+        }
+        for dir in Direction::ALL.into_iter().filter(|d| board.is_void(pos.step(*d))) {
+            let (there, facing) = cube.wrap(pos, dir);
+            assert!(!board.is_void(there), "{} {:?} wrapped off the board to {}", pos, dir, there);
+            assert_eq!(cube.wrap(there, facing.opposite()), (pos, dir.opposite()), "{} {:?} via {}", pos, dir, there);
+        }
+    }
+
+    // walking 4 cube edges in a straight line goes all the way round
+    let start = board.start();
+    let lap = [Step::Forward(16)];
+    assert_eq!(board.walk(&lap, |p, d| cube.wrap(p, d)), (start, Direction::Right));
+}
+
+#[test]
+fn test_validate() {
+    // 'x' isn't a tile, there are only 12 tiles, & 'Q' isn't a turn
+    crate::validate::assert_issue_lines(validate, &["  ..", "  .x", "....", "....", "", "10R5Q"], &[1, 2, 6]);
+}
@@ -0,0 +1,271 @@
+
+use std::collections::{HashMap, HashSet};
+
+use crate::Part;
+use crate::grid::{self, Coord, Direction, Grid};
+use crate::validate::Issue;
+
+const ELF: char = '#';
+const GROUND: char = '.';
+/// how many rounds part 1 runs for.
+const ROUNDS: usize = 10;
+/// the order the elves consider moving in, starting one further along each round.
+const PROPOSALS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+struct Grove {
+    elves: HashSet<Coord>,
+    rounds: usize,
+}
+
+impl Grove {
+    fn parse(input: Box<dyn Iterator<Item = String>>) -> Self {
+        let map = Grid::parse(input, |c| match c {
+            ELF => Some(true),
+            GROUND => Some(false),
+            _ => None,
+        })
+            .unwrap_or_else(|e| panic!("invalid map: {}", e));
+
+        let elves = map.iter()
+            .filter(|(_, elf)| **elf)
+            .map(|(c, _)| c)
+            .collect();
+        Self { elves, rounds: 0 }
+    }
+
+    /// the 3 tiles an elf checks before moving in `dir`: straight ahead, and either side of it.
+    fn looking(elf: Coord, dir: Direction) -> [Coord; 3] {
+        let ahead = elf.step(dir);
+        [ahead, ahead.step(dir.turn_left()), ahead.step(dir.turn_right())]
+    }
+
+    /// runs a round, returning whether any elf moved.
+    fn round(&mut self) -> bool {
+        let order: Vec<Direction> = (0..PROPOSALS.len())
+            .map(|i| PROPOSALS[(self.rounds + i) % PROPOSALS.len()])
+            .collect();
+        self.rounds += 1;
+
+        // each tile proposed, and the elf proposing it (or `None` once a second elf does too)
+        let mut proposals: HashMap<Coord, Option<Coord>> = HashMap::new();
+        for elf in &self.elves {
+            if elf.neighbours8().all(|n| !self.elves.contains(&n)) {
+                continue;
+            }
+
+            let proposal = order.iter()
+                .find(|dir| Grove::looking(*elf, **dir).iter().all(|c| !self.elves.contains(c)))
+                .map(|dir| elf.step(*dir));
+            if let Some(to) = proposal {
+                proposals.entry(to)
+                    .and_modify(|from| *from = None)
+                    .or_insert(Some(*elf));
+            }
+        }
+
+        let mut moved = false;
+        for (to, from) in proposals {
+            if let Some(from) = from {
+                self.elves.remove(&from);
+                self.elves.insert(to);
+                moved = true;
+            }
+        }
+        moved
+    }
+
+    /// the top left & bottom right corners of the smallest rectangle holding every elf.
+    fn bounds(&self) -> (Coord, Coord) {
+        let xs = || self.elves.iter().map(|e| e.x);
+        let ys = || self.elves.iter().map(|e| e.y);
+        (
+            Coord::new(xs().min().unwrap_or(0), ys().min().unwrap_or(0)),
+            Coord::new(xs().max().unwrap_or(0), ys().max().unwrap_or(0)),
+        )
+    }
+
+    fn empty_ground(&self) -> usize {
+        let (min, max) = self.bounds();
+        let area = (max.x - min.x + 1) * (max.y - min.y + 1);
+        area as usize - self.elves.len()
+    }
+
+    /// draws the smallest rectangle holding every elf, like the puzzle text does.
+    fn render(&self) -> String {
+        let (min, max) = self.bounds();
+
+        (min.y..=max.y)
+            .map(|y| (min.x..=max.x)
+                .map(|x| if self.elves.contains(&Coord::new(x, y)) { ELF } else { GROUND })
+                .collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// runs up to `rounds` more rounds, stopping early once the elves stop moving.
+    fn run(&mut self, rounds: usize) {
+        for _ in 0..rounds {
+            if !self.round() {
+                break;
+            }
+        }
+    }
+
+    /// runs the rounds for `part`, returning the answer.
+    fn spread(&mut self, part: &Part) -> usize {
+        match part {
+            Part::Part1 => {
+                for _ in 0..ROUNDS {
+                    self.round();
+                }
+                self.empty_ground()
+            },
+            Part::Part2 => {
+                while self.round() {}
+                self.rounds
+            },
+        }
+    }
+}
+
+
+pub(crate) fn solve(input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+    let mut grove = Grove::parse(input);
+
+    format!("{}", grove.spread(&part))
+}
+
+/// draws where the elves are after `rounds` rounds (or once they stop moving, if that's sooner).
+pub(crate) fn render(input: Box<dyn Iterator<Item = String>>, rounds: usize) -> String {
+    let mut grove = Grove::parse(input);
+    grove.run(rounds);
+
+    grove.render()
+}
+
+/// also draws where the elves end up.
+pub(crate) fn explain(input: Box<dyn Iterator<Item = String>>, part: Part) -> (String, String) {
+    let mut grove = Grove::parse(input);
+    let answer = grove.spread(&part);
+
+    (format!("{}", answer), format!("after round {}:\n{}", grove.rounds, grove.render()))
+}
+
+pub(crate) fn validate(input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut width: Option<usize> = None;
+    let mut elves = 0;
+
+    for (i, line) in grid::grid_lines(input).into_iter().enumerate() {
+        let line_no = i + 1;
+
+        if let Some((col, c)) = line.chars().enumerate().find(|(_, c)| *c != ELF && *c != GROUND) {
+            issues.push(Issue::new(line_no, format!("tiles should be '{}' or '{}', found '{}' at column {}", ELF, GROUND, c, col + 1)));
+        }
+        elves += line.chars().filter(|c| *c == ELF).count();
+
+        let len = line.chars().count();
+        match width {
+            None if len == 0 => issues.push(Issue::new(line_no, "row is empty")),
+            None => width = Some(len),
+            Some(w) if w != len => issues.push(Issue::new(line_no, format!("row is {} tiles wide, expected {}", len, w))),
+            Some(_) => {},
+        }
+    }
+
+    if elves == 0 {
+        issues.push(Issue::new(1, "there are no elves"));
+    }
+
+    issues.sort_by_key(|i| i.line);
+    issues
+}
+
+
+#[cfg(test)]
+const EXAMPLE: &str = r"....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..";
+
+#[test]
+// sanity check vs example input
+fn test_input() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let output = solve(Box::new(lines.clone()), Part::Part1);
+    assert_eq!(output.as_str(), "110");
+
+    let output = solve(Box::new(lines), Part::Part2);
+    assert_eq!(output.as_str(), "20");
+}
+
+#[test]
+fn test_render() {
+    let lines = [".....", "..##.", "..#..", ".....", "..##.", "....."]
+        .into_iter()
+        .map(String::from);
+    let mut grove = Grove::parse(Box::new(lines));
+
+    // the puzzle's small example, round by round
+    let expected = [
+        "##\n..\n#.\n.#\n#.",
+        ".##.\n#...\n...#\n....\n.#..",
+        "..#..\n....#\n#....\n....#\n.....\n..#..",
+    ];
+    for drawing in expected {
+        assert!(grove.round());
+        assert_eq!(grove.render(), drawing);
+    }
+    assert!(!grove.round());
+    assert_eq!(grove.rounds, 4);
+}
+
+#[test]
+// the larger example's drawings, partway through
+fn test_render_after() {
+    let lines = EXAMPLE.split('\n')
+        .map(String::from);
+
+    let round_1 = [
+        ".....#...",
+        "...#...#.",
+        ".#..#.#..",
+        ".....#..#",
+        "..#.#.##.",
+        "#..#.#...",
+        "#.#.#.##.",
+        ".........",
+        "..#..#...",
+    ];
+    assert_eq!(render(Box::new(lines.clone()), 1), round_1.join("\n"));
+
+    let round_10 = [
+        "......#.....",
+        "..........#.",
+        ".#.#..#.....",
+        ".....#......",
+        "..#.....#..#",
+        "#......##...",
+        "....##......",
+        ".#........#.",
+        "...#.#..#...",
+        "............",
+        "...#..#..#..",
+    ];
+    assert_eq!(render(Box::new(lines.clone()), 10), round_10.join("\n"));
+
+    // nobody moves after round 20, so later rounds draw the same thing
+    assert_eq!(render(Box::new(lines.clone()), 50), render(Box::new(lines), 20));
+}
+
+#[test]
+fn test_validate() {
+    crate::validate::assert_issue_lines(validate, &["..#", ".x.", "...#"], &[2, 3]);
+    // like `solve`, blank lines at the end are fine
+    crate::validate::assert_issue_lines(validate, &["..#", "...", ""], &[]);
+}
@@ -0,0 +1,315 @@
+//! C ABI for calling the solvers from other languages (C/C++, Python via ctypes, etc).
+//!
+//! The header for these bindings is checked in at `include/advent.h`, and generated by [`header`].
+//! The function prototypes come from the `extern "C"` signatures themselves (see [`CType`]), so they can't drift from the Rust side.
+//! (`test_header_is_current` fails if the checked in copy falls behind, `ADVENT_BLESS_HEADER=1 cargo test` rewrites it)
+
+use std::{
+    cell::Cell,
+    ffi::{c_char, CString},
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use crate::{Day, Part, Solve, input::normalize};
+
+/// the only puzzle year this crate has solvers for.
+const YEAR: u32 = 2022;
+
+thread_local! {
+    /// set while this thread is running a solver for [`advent_solve`].
+    static IN_FFI: Cell<bool> = const { Cell::new(false) };
+}
+
+static QUIET_HOOK: Once = Once::new();
+
+/// wraps the panic hook (once per process) so panics inside [`advent_solve`] aren't printed,
+/// their message goes back in `text` instead. panics anywhere else still reach the previous hook.
+fn install_quiet_hook() {
+    QUIET_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !IN_FFI.with(Cell::get) {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// Result codes returned in [`AdventAnswer::status`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdventStatus {
+    Ok = 0,
+    InvalidYear = 1,
+    InvalidDay = 2,
+    InvalidPart = 3,
+    InvalidInput = 4,
+    SolverPanicked = 5,
+}
+
+impl AdventStatus {
+    const ALL: [AdventStatus; 6] = [
+        AdventStatus::Ok,
+        AdventStatus::InvalidYear,
+        AdventStatus::InvalidDay,
+        AdventStatus::InvalidPart,
+        AdventStatus::InvalidInput,
+        AdventStatus::SolverPanicked,
+    ];
+
+    fn c_name(&self) -> &'static str {
+        match self {
+            AdventStatus::Ok => "ADVENT_OK",
+            AdventStatus::InvalidYear => "ADVENT_INVALID_YEAR",
+            AdventStatus::InvalidDay => "ADVENT_INVALID_DAY",
+            AdventStatus::InvalidPart => "ADVENT_INVALID_PART",
+            AdventStatus::InvalidInput => "ADVENT_INVALID_INPUT",
+            AdventStatus::SolverPanicked => "ADVENT_SOLVER_PANICKED",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            AdventStatus::Ok => "`text` holds the answer",
+            AdventStatus::InvalidYear => "only 2022 is supported",
+            AdventStatus::InvalidDay => "`day` must be 1-25",
+            AdventStatus::InvalidPart => "`part` must be 1 or 2",
+            AdventStatus::InvalidInput => "the input was NULL or not valid UTF-8",
+            AdventStatus::SolverPanicked => "the solver failed (or isn't implemented yet), `text` holds the reason",
+        }
+    }
+}
+
+/// The result of [`advent_solve`].
+/// `text` is the answer on success, or an error message otherwise.
+/// it's owned by the caller and must be released with [`advent_free`].
+#[repr(C)]
+pub struct AdventAnswer {
+    pub status: AdventStatus,
+    pub text: *mut c_char,
+}
+
+impl AdventAnswer {
+    fn new(status: AdventStatus, text: String) -> Self {
+        // answers & messages never contain NULs, but don't hand back garbage if one sneaks in.
+        let text = CString::new(text.replace('\0', ""))
+            .expect("NULs were stripped");
+
+        Self { status, text: text.into_raw() }
+    }
+}
+
+/// Solves `part` of `day` for the puzzle input in `input_ptr[..len]` (UTF-8 text).
+///
+/// # Safety
+/// `input_ptr` must either be NULL or point to `len` readable bytes,
+/// and the returned `text` must be released with [`advent_free`] exactly once.
+#[no_mangle]
+pub unsafe extern "C" fn advent_solve(year: u32, day: u32, part: u32, input_ptr: *const u8, len: usize) -> AdventAnswer {
+    if year != YEAR {
+        return AdventAnswer::new(AdventStatus::InvalidYear, format!("no solvers for {}, only {}", year, YEAR));
+    }
+    let Ok(day) = Day::try_from(day) else {
+        return AdventAnswer::new(AdventStatus::InvalidDay, format!("invalid day {}", day));
+    };
+    let Ok(part) = Part::try_from(part) else {
+        return AdventAnswer::new(AdventStatus::InvalidPart, format!("invalid part {}", part));
+    };
+
+    if input_ptr.is_null() {
+        return AdventAnswer::new(AdventStatus::InvalidInput, "input is NULL".into());
+    }
+    // SAFETY: the caller guarantees `input_ptr` points to `len` readable bytes.
+    let bytes = unsafe { std::slice::from_raw_parts(input_ptr, len) };
+    let Ok(input) = std::str::from_utf8(bytes) else {
+        return AdventAnswer::new(AdventStatus::InvalidInput, "input is not valid UTF-8".into());
+    };
+
+    let lines: Vec<String> = input.lines().map(String::from).collect();
+    let buf = normalize(Box::new(lines.into_iter()));
+
+    // unwinding across the FFI boundary is UB, so solver panics become an error code.
+    // the message goes back in `text`, so the hook is told not to also print it to stderr.
+    install_quiet_hook();
+    IN_FFI.with(|f| f.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| day.solve(buf, part)));
+    IN_FFI.with(|f| f.set(false));
+
+    match result {
+        Ok(answer) => AdventAnswer::new(AdventStatus::Ok, answer),
+        Err(payload) => {
+            let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "solver panicked".into());
+            AdventAnswer::new(AdventStatus::SolverPanicked, message)
+        },
+    }
+}
+
+/// Releases the `text` of an [`AdventAnswer`].
+///
+/// # Safety
+/// `text` must be NULL or a pointer returned by [`advent_solve`] that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn advent_free(text: *mut c_char) {
+    if text.is_null() {
+        return;
+    }
+    // SAFETY: the caller guarantees this came from `CString::into_raw` in `advent_solve`.
+    drop(unsafe { CString::from_raw(text) });
+}
+
+/// a type that crosses the C ABI, and how it's spelled in the header.
+trait CType {
+    const NAME: &'static str;
+}
+
+impl CType for () { const NAME: &'static str = "void"; }
+impl CType for u32 { const NAME: &'static str = "uint32_t"; }
+impl CType for usize { const NAME: &'static str = "size_t"; }
+impl CType for *const u8 { const NAME: &'static str = "const uint8_t *"; }
+impl CType for *mut c_char { const NAME: &'static str = "char *"; }
+impl CType for AdventAnswer { const NAME: &'static str = "AdventAnswer"; }
+
+/// an `extern "C"` function pointer, as its return type & parameter types in C.
+trait CFunction {
+    fn c_types() -> (&'static str, Vec<&'static str>);
+}
+
+impl<R: CType, A: CType> CFunction for unsafe extern "C" fn(A) -> R {
+    fn c_types() -> (&'static str, Vec<&'static str>) {
+        (R::NAME, vec![A::NAME])
+    }
+}
+
+impl<R: CType, A: CType, B: CType, C: CType, D: CType, E: CType> CFunction for unsafe extern "C" fn(A, B, C, D, E) -> R {
+    fn c_types() -> (&'static str, Vec<&'static str>) {
+        (R::NAME, vec![A::NAME, B::NAME, C::NAME, D::NAME, E::NAME])
+    }
+}
+
+/// the C prototype for `function`, called `name` with parameters named `params`.
+fn prototype<F: CFunction>(name: &str, _function: F, params: &[&str]) -> String {
+    let (returns, types) = F::c_types();
+    assert_eq!(types.len(), params.len(), "{} needs a name for each parameter", name);
+
+    // pointer types are written with the `*` against the name
+    let declare = |t: &str, name: &str| if t.ends_with('*') { format!("{}{}", t, name) } else { format!("{} {}", t, name) };
+
+    let params: Vec<String> = types.iter()
+        .zip(params)
+        .map(|(t, p)| declare(t, p))
+        .collect();
+    format!("{}({});\n", declare(returns, name), params.join(", "))
+}
+
+/// the C header for the bindings above.
+pub fn header() -> String {
+    let mut header = String::new();
+
+    header += "/* Generated by `header()` in src/ffi/mod.rs, edit that instead. */\n";
+    header += "#ifndef ADVENT_H\n#define ADVENT_H\n\n";
+    header += "#include <stddef.h>\n#include <stdint.h>\n\n";
+    header += "#ifdef __cplusplus\nextern \"C\" {\n#endif\n\n";
+
+    header += "typedef enum AdventStatus {\n";
+    for status in AdventStatus::ALL {
+        header += &format!("    /* {} */\n    {} = {},\n", status.description(), status.c_name(), status as i32);
+    }
+    header += "} AdventStatus;\n\n";
+
+    header += "/* `text` is the answer on success, or an error message otherwise.\n";
+    header += "   it's owned by the caller and must be released with `advent_free`. */\n";
+    header += "typedef struct AdventAnswer {\n    AdventStatus status;\n    char *text;\n} AdventAnswer;\n\n";
+
+    header += "/* Solves `part` of `day` for the puzzle input in `input_ptr[..len]` (UTF-8 text). */\n";
+    header += &prototype("advent_solve", advent_solve as unsafe extern "C" fn(_, _, _, _, _) -> _, &["year", "day", "part", "input_ptr", "len"]);
+    header += "\n/* Releases the `text` of an `AdventAnswer`. */\n";
+    header += &prototype("advent_free", advent_free as unsafe extern "C" fn(_) -> _, &["text"]);
+    header += "\n";
+
+    header += "#ifdef __cplusplus\n}\n#endif\n\n#endif /* ADVENT_H */\n";
+    header
+}
+
+
+#[cfg(test)]
+fn solve_str(year: u32, day: u32, part: u32, input: &str) -> (AdventStatus, String) {
+    let answer = unsafe { advent_solve(year, day, part, input.as_ptr(), input.len()) };
+    let text = unsafe { std::ffi::CStr::from_ptr(answer.text) }
+        .to_string_lossy()
+        .into_owned();
+    unsafe { advent_free(answer.text) };
+
+    (answer.status, text)
+}
+
+#[test]
+fn test_advent_solve() {
+    const EXAMPLE: &str = "A Y\r\nB X\r\nC Z\r\n";
+
+    assert_eq!(solve_str(2022, 2, 1, EXAMPLE), (AdventStatus::Ok, "15".into()));
+    assert_eq!(solve_str(2022, 2, 2, EXAMPLE), (AdventStatus::Ok, "12".into()));
+
+    assert_eq!(solve_str(2021, 2, 1, EXAMPLE).0, AdventStatus::InvalidYear);
+    assert_eq!(solve_str(2022, 0, 1, EXAMPLE).0, AdventStatus::InvalidDay);
+    assert_eq!(solve_str(2022, 26, 1, EXAMPLE).0, AdventStatus::InvalidDay);
+    assert_eq!(solve_str(2022, 2, 3, EXAMPLE).0, AdventStatus::InvalidPart);
+
+    let answer = unsafe { advent_solve(2022, 2, 1, std::ptr::null(), 0) };
+    assert_eq!(answer.status, AdventStatus::InvalidInput);
+    unsafe { advent_free(answer.text) };
+}
+
+#[test]
+fn test_advent_solve_panic() {
+    let (status, message) = solve_str(2022, 9, 1, "R 4\nnotamove");
+    assert_eq!(status, AdventStatus::SolverPanicked);
+    assert_eq!(message, "line 2, column 9: expected ' ', found end of line");
+}
+
+#[test]
+// callers on several threads at once each get their own panic back
+fn test_advent_solve_threads() {
+    let handles: Vec<_> = (0..8)
+        .map(|i| std::thread::spawn(move || {
+            if i % 2 == 0 {
+                solve_str(2022, 9, 1, "R 4\nnotamove").0
+            } else {
+                solve_str(2022, 2, 1, "A Y").0
+            }
+        }))
+        .collect();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        let expected = if i % 2 == 0 { AdventStatus::SolverPanicked } else { AdventStatus::Ok };
+        assert_eq!(handle.join().expect("advent_solve shouldn't unwind"), expected);
+    }
+}
+
+#[test]
+// keeps `include/advent.h` in sync with the bindings.
+fn test_header_is_current() {
+    const PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/include/advent.h");
+
+    if std::env::var_os("ADVENT_BLESS_HEADER").is_some() {
+        std::fs::write(PATH, header()).expect("should be able to write the header");
+    }
+
+    let current = std::fs::read_to_string(PATH).unwrap_or_default();
+    assert!(current == header(), "include/advent.h is out of date, regenerate it with `ADVENT_BLESS_HEADER=1 cargo test`");
+}
+
+#[test]
+// the header's prototypes follow the Rust signatures
+fn test_prototype() {
+    unsafe extern "C" fn example(_input: *const u8, _a: u32, _b: u32, _c: u32, _len: usize) -> *mut c_char {
+        std::ptr::null_mut()
+    }
+
+    assert_eq!(
+        prototype("example", example as unsafe extern "C" fn(_, _, _, _, _) -> _, &["input", "a", "b", "c", "len"]),
+        "char *example(const uint8_t *input, uint32_t a, uint32_t b, uint32_t c, size_t len);\n",
+    );
+}
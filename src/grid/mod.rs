@@ -0,0 +1,461 @@
+//! Shared 2D grid & coordinate types for the grid based puzzles.
+//!
+//! Coordinates follow the puzzle text layout: `x` grows to the right (column),
+//! and `y` grows downwards (row), so `(0, 0)` is the top left of a parsed grid.
+//! `Coord3` is the same idea in 3D, for the puzzles that happen in a volume.
+
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Index, IndexMut, Sub},
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Coord {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Coord {
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    /// the neighbouring coordinate in `direction`.
+    pub fn step(&self, direction: Direction) -> Self {
+        *self + direction.delta()
+    }
+
+    /// moves one step towards `other`, diagonally if needed.
+    pub fn step_towards(&self, other: &Coord) -> Self {
+        Self::new(
+            self.x + (other.x - self.x).signum(),
+            self.y + (other.y - self.y).signum(),
+        )
+    }
+
+    /// the 4 orthogonal neighbours (up, down, left, right).
+    pub fn neighbours4(&self) -> impl Iterator<Item = Coord> + '_ {
+        Direction::ALL.into_iter().map(|d| self.step(d))
+    }
+
+    /// all 8 neighbours, including diagonals.
+    pub fn neighbours8(&self) -> impl Iterator<Item = Coord> + '_ {
+        (-1..=1).flat_map(move |dy| {
+            (-1..=1)
+                .filter(move |dx| *dx != 0 || dy != 0)
+                .map(move |dx| Coord::new(self.x + dx, self.y + dy))
+        })
+    }
+
+    pub fn manhattan_dist(&self, other: &Coord) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// distance when a diagonal step counts as 1 (ie: a king's move).
+    pub fn chebyshev_dist(&self, other: &Coord) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+}
+
+impl Add for Coord {
+    type Output = Coord;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Coord::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Coord {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl Sub for Coord {
+    type Output = Coord;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Coord::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Display for Coord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("({},{})", self.x, self.y))
+    }
+}
+
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Coord3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+impl Coord3 {
+    pub const fn new(x: isize, y: isize, z: isize) -> Self {
+        Self { x, y, z }
+    }
+
+    /// the 6 neighbours sharing a face (one step along each axis, both ways).
+    pub fn neighbours6(&self) -> impl Iterator<Item = Coord3> + '_ {
+        const DELTAS: [Coord3; 6] = [
+            Coord3::new(1, 0, 0), Coord3::new(-1, 0, 0),
+            Coord3::new(0, 1, 0), Coord3::new(0, -1, 0),
+            Coord3::new(0, 0, 1), Coord3::new(0, 0, -1),
+        ];
+        DELTAS.into_iter().map(|d| *self + d)
+    }
+
+    pub fn manhattan_dist(&self, other: &Coord3) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+}
+
+impl Add for Coord3 {
+    type Output = Coord3;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Coord3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Coord3 {
+    type Output = Coord3;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Coord3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Display for Coord3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("({},{},{})", self.x, self.y, self.z))
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    pub fn delta(&self) -> Coord {
+        match self {
+            Direction::Up => Coord::new(0, -1),
+            Direction::Down => Coord::new(0, 1),
+            Direction::Left => Coord::new(-1, 0),
+            Direction::Right => Coord::new(1, 0),
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    pub fn turn_left(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub fn turn_right(&self) -> Self {
+        self.turn_left().opposite()
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    Empty,
+    /// `row` (0-based) has a different width than the rows before it.
+    Ragged { row: usize, width: usize, expected: usize },
+    /// the cell parser rejected `c` at `at`.
+    InvalidCell { at: Coord, c: char },
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::Empty => f.write_str("grid is empty"),
+            GridError::Ragged { row, width, expected } =>
+                f.write_fmt(format_args!("row {} is {} wide, expected {}", row + 1, width, expected)),
+            GridError::InvalidCell { at, c } =>
+                f.write_fmt(format_args!("invalid cell '{}' at {}", c, at)),
+        }
+    }
+}
+
+
+/// A dense, rectangular grid stored in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// the grid flipped over its main diagonal (rows become columns).
+    pub fn transpose(&self) -> Self {
+        let cells = (0..self.width)
+            .flat_map(|x| (0..self.height).map(move |y| (x, y)))
+            .map(|(x, y)| self.cells[y * self.width + x].clone())
+            .collect();
+
+        Self { width: self.height, height: self.width, cells }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, GridError> {
+        let width = rows.first().map(|r| r.len()).ok_or(GridError::Empty)?;
+        let height = rows.len();
+
+        if let Some((row, r)) = rows.iter().enumerate().find(|(_, r)| r.len() != width) {
+            return Err(GridError::Ragged { row, width: r.len(), expected: width });
+        }
+
+        Ok(Self { width, height, cells: rows.into_iter().flatten().collect() })
+    }
+
+    /// parses a grid of characters (one row per line), converting each with `cell`.
+    /// blank lines at the end (ie: from a trailing newline) are ignored.
+    pub fn parse<I, F>(lines: I, mut cell: F) -> Result<Self, GridError>
+    where
+        I: IntoIterator<Item = String>,
+        F: FnMut(char) -> Option<T>,
+    {
+        let rows = grid_lines(lines).into_iter()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, c)| cell(c).ok_or(GridError::InvalidCell { at: Coord::new(x as isize, y as isize), c }))
+                    .collect::<Result<Vec<T>, GridError>>()
+            })
+            .collect::<Result<Vec<Vec<T>>, GridError>>()?;
+
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, c: Coord) -> bool {
+        c.x >= 0 && c.y >= 0 && (c.x as usize) < self.width && (c.y as usize) < self.height
+    }
+
+    fn index_of(&self, c: Coord) -> Option<usize> {
+        if self.in_bounds(c) {
+            Some(c.y as usize * self.width + c.x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, c: Coord) -> Option<&T> {
+        self.index_of(c).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, c: Coord) -> Option<&mut T> {
+        self.index_of(c).map(|i| &mut self.cells[i])
+    }
+
+    /// every coordinate in the grid, row by row.
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Coord::new(x as isize, y as isize)))
+    }
+
+    /// every cell (with its coordinate), row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+
+    /// the first coordinate whose cell matches `pred`.
+    pub fn find(&self, pred: impl Fn(&T) -> bool) -> Option<Coord> {
+        self.iter().find(|(_, v)| pred(v)).map(|(c, _)| c)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "row {} is out of bounds, the grid is {} tall", y, self.height);
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn col(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} is out of bounds, the grid is {} wide", x, self.width);
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    /// the in-bounds orthogonal neighbours of `c`.
+    pub fn neighbours4(&self, c: Coord) -> impl Iterator<Item = Coord> + '_ {
+        Direction::ALL.into_iter()
+            .map(move |d| c.step(d))
+            .filter(|n| self.in_bounds(*n))
+    }
+
+    /// the in-bounds neighbours of `c`, including diagonals.
+    pub fn neighbours8(&self, c: Coord) -> impl Iterator<Item = Coord> + '_ {
+        (-1..=1)
+            .flat_map(move |dy| (-1..=1).map(move |dx| c + Coord::new(dx, dy)))
+            .filter(move |n| *n != c && self.in_bounds(*n))
+    }
+
+    /// walks from `from` (exclusive) in `direction` until the edge of the grid.
+    /// `from` doesn't need to be in bounds, so a ray can start just outside an edge.
+    pub fn ray(&self, from: Coord, direction: Direction) -> impl Iterator<Item = (Coord, &T)> + '_ {
+        let delta = direction.delta();
+        std::iter::successors(Some(from + delta), move |c| Some(*c + delta))
+            .map_while(move |c| self.get(c).map(|v| (c, v)))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    /// draws the grid, one line per row.
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&f).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// the lines `Grid::parse` reads a grid from: all of them, except any blank ones at the end.
+/// validators use this too, so they accept exactly what `parse` does.
+pub fn grid_lines(lines: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut lines: Vec<String> = lines.into_iter().collect();
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, c: Coord) -> &Self::Output {
+        self.get(c).unwrap_or_else(|| panic!("{} is outside the {}x{} grid", c, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, c: Coord) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(c).unwrap_or_else(|| panic!("{} is outside the {}x{} grid", c, width, height))
+    }
+}
+
+
+#[cfg(test)]
+fn test_grid() -> Grid<char> {
+    let lines = ["abc", "def"].into_iter().map(String::from);
+    Grid::parse(lines, Some).unwrap()
+}
+
+#[test]
+fn test_parse() {
+    let grid = test_grid();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[Coord::new(2, 1)], 'f');
+    assert_eq!(grid.get(Coord::new(3, 0)), None);
+    assert_eq!(grid.get(Coord::new(0, -1)), None);
+
+    let ragged = ["abc", "de"].into_iter().map(String::from);
+    assert_eq!(Grid::parse(ragged, Some), Err(GridError::Ragged { row: 1, width: 2, expected: 3 }));
+
+    let digits = ["12", "3x"].into_iter().map(String::from);
+    assert_eq!(Grid::parse(digits, |c| c.to_digit(10)), Err(GridError::InvalidCell { at: Coord::new(1, 1), c: 'x' }));
+
+    let trailing = ["abc", "def", "", ""].into_iter().map(String::from);
+    assert_eq!(Grid::parse(trailing, Some), Ok(test_grid()));
+
+    let blank = ["", ""].into_iter().map(String::from);
+    assert_eq!(Grid::parse(blank, Some), Err(GridError::Empty));
+}
+
+#[test]
+fn test_views() {
+    let grid = test_grid();
+    assert_eq!(grid.row(1), &['d', 'e', 'f']);
+    assert_eq!(grid.col(1).collect::<String>(), "be");
+    assert_eq!(grid.transpose().render(|c| *c), "ad\nbe\ncf");
+    assert_eq!(grid.find(|c| *c == 'e'), Some(Coord::new(1, 1)));
+}
+
+#[test]
+#[should_panic(expected = "column 3 is out of bounds")]
+fn test_col_out_of_bounds() {
+    // would otherwise wrap around to the start of the next row
+    let _ = test_grid().col(3);
+}
+
+#[test]
+fn test_neighbours_and_rays() {
+    let grid = test_grid();
+
+    let mut n4: Vec<char> = grid.neighbours4(Coord::new(0, 0)).map(|c| grid[c]).collect();
+    n4.sort();
+    assert_eq!(n4, vec!['b', 'd']);
+    assert_eq!(grid.neighbours8(Coord::new(1, 0)).count(), 5);
+    assert_eq!(Coord::new(5, 5).neighbours8().count(), 8);
+
+    let right: String = grid.ray(Coord::new(0, 1), Direction::Right).map(|(_, c)| c).collect();
+    assert_eq!(right, "ef");
+    let from_outside: String = grid.ray(Coord::new(3, 0), Direction::Left).map(|(_, c)| c).collect();
+    assert_eq!(from_outside, "cba");
+    assert_eq!(grid.ray(Coord::new(0, 0), Direction::Up).count(), 0);
+}
+
+#[test]
+fn test_coord() {
+    let a = Coord::new(0, 0);
+    let b = Coord::new(3, -2);
+    assert_eq!(a.manhattan_dist(&b), 5);
+    assert_eq!(a.chebyshev_dist(&b), 3);
+    assert_eq!(a.step_towards(&b), Coord::new(1, -1));
+    assert_eq!(Direction::Up.turn_right(), Direction::Right);
+    assert_eq!(Direction::Right.turn_left(), Direction::Up);
+}
+
+#[test]
+fn test_coord3() {
+    let a = Coord3::new(1, 2, 3);
+    let neighbours: Vec<Coord3> = a.neighbours6().collect();
+    assert_eq!(neighbours.len(), 6);
+    assert!(neighbours.iter().all(|n| n.manhattan_dist(&a) == 1));
+    assert!(neighbours.contains(&Coord3::new(1, 2, 2)));
+    assert_eq!(a - Coord3::new(1, 1, 1), Coord3::new(0, 1, 2));
+    assert_eq!(a.to_string(), "(1,2,3)");
+}
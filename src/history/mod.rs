@@ -0,0 +1,270 @@
+use std::{
+    fmt::Display,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    time::Duration,
+};
+
+use clap::ValueEnum;
+
+use crate::{Day, Part};
+
+/// default location of the history file, relative to where the tool is run.
+pub const HISTORY_FILE: &str = ".advent-history";
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// A single CLI run, stored as one tab separated line in the history file.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Record {
+    /// seconds since the unix epoch.
+    pub timestamp: u64,
+    pub day: Day,
+    pub part: Part,
+    pub input_hash: u64,
+    pub answer: String,
+    pub duration: Duration,
+    pub revision: String,
+}
+
+impl Record {
+    fn to_line(&self) -> String {
+        format!("{}\t{}\t{}\t{:016x}\t{}\t{}\t{}",
+            self.timestamp,
+            value_name(&self.day),
+            value_name(&self.part),
+            self.input_hash,
+            self.duration.as_micros(),
+            self.revision,
+            // keep multi-line answers (ie: day10 part 2) on a single line
+            self.answer.replace('\\', "\\\\").replace('\n', "\\n"),
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(7, '\t');
+
+        let timestamp = fields.next()?.parse().ok()?;
+        let day = Day::from_str(fields.next()?, true).ok()?;
+        let part = Part::from_str(fields.next()?, true).ok()?;
+        let input_hash = u64::from_str_radix(fields.next()?, 16).ok()?;
+        let duration = Duration::from_micros(fields.next()?.parse().ok()?);
+        let revision = fields.next()?.to_string();
+        let answer = unescape(fields.next()?);
+
+        Some(Self { timestamp, day, part, input_hash, answer, duration, revision })
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}  {:?}  {:016x}  {:>12}  {:<12}  {}",
+            format_timestamp(self.timestamp),
+            self.part,
+            self.input_hash,
+            format!("{:.2?}", self.duration),
+            self.revision,
+            self.answer.replace('\n', "\\n"),
+        ))
+    }
+}
+
+fn value_name<T: ValueEnum>(value: &T) -> String {
+    value.to_possible_value()
+        .expect("no values are skipped")
+        .get_name()
+        .to_string()
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// formats a unix timestamp as `YYYY-MM-DD HH:MM:SS` (UTC).
+fn format_timestamp(timestamp: u64) -> String {
+    let (days, secs) = (timestamp / SECS_PER_DAY, timestamp % SECS_PER_DAY);
+
+    // civil-from-days, see: http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, secs / 3600, (secs / 60) % 60, secs % 60)
+}
+
+/// the git revision this binary was built from (if it was built from a git checkout).
+pub fn revision() -> &'static str {
+    option_env!("ADVENT_GIT_REV").unwrap_or("unknown")
+}
+
+/// hashes the whole input (FNV-1a) up front, so a solver that stops reading early
+/// still gets recorded against everything it was given.
+/// returns the buffered input for the solver to read from, along with the hash.
+pub fn hash_input(input: Box<dyn Iterator<Item = String>>) -> (Box<dyn Iterator<Item = String>>, u64) {
+    let lines: Vec<String> = input.collect();
+
+    let mut hash = FNV_OFFSET;
+    for byte in lines.iter().flat_map(|line| line.bytes().chain(std::iter::once(b'\n'))) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+
+    (Box::new(lines.into_iter()), hash)
+}
+
+/// appends `record` to the history file, returning any earlier record for the
+/// same day, part and input whose answer differs from this one.
+pub fn append(path: &Path, record: &Record) -> std::io::Result<Option<Record>> {
+    let conflict = load(path)?.into_iter()
+        .rev()
+        .find(|r| r.day == record.day &&
+            r.part == record.part &&
+            r.input_hash == record.input_hash &&
+            r.answer != record.answer);
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(format!("{}\n", record.to_line()).as_bytes())?;
+
+    Ok(conflict)
+}
+
+/// loads every record in the history file, skipping any lines that don't parse.
+pub fn load(path: &Path) -> std::io::Result<Vec<Record>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    Ok(
+        BufReader::new(file).lines()
+            .map_while(Result::ok)
+            .filter_map(|line| Record::from_line(&line))
+            .collect()
+    )
+}
+
+/// renders the history for a single day, oldest first.
+/// any run whose answer differs from an earlier run on the same input is flagged.
+pub fn report(records: &[Record], day: &Day) -> String {
+    let records: Vec<&Record> = records.iter().filter(|r| &r.day == day).collect();
+    if records.is_empty() {
+        return format!("no history for {}", value_name(day));
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut changed = 0_usize;
+
+    for (i, record) in records.iter().enumerate() {
+        let previous = records[..i].iter()
+            .rev()
+            .find(|r| r.part == record.part && r.input_hash == record.input_hash);
+
+        match previous {
+            Some(prev) if prev.answer != record.answer => {
+                changed += 1;
+                lines.push(format!("{}    <-- ANSWER CHANGED (was '{}' at {})",
+                    record, prev.answer.replace('\n', "\\n"), prev.revision));
+            },
+            Some(prev) => {
+                let delta = record.duration.as_secs_f64() / prev.duration.as_secs_f64().max(f64::EPSILON);
+                lines.push(format!("{}    ({:.2}x previous time)", record, delta));
+            },
+            None => lines.push(format!("{}", record)),
+        }
+    }
+
+    if changed > 0 {
+        lines.push(format!("\n!! {} run(s) changed the answer for an input that was already solved, a solver may be broken !!", changed));
+    }
+
+    lines.join("\n")
+}
+
+
+#[cfg(test)]
+fn test_record(timestamp: u64, answer: &str, micros: u64) -> Record {
+    Record {
+        timestamp,
+        day: Day::Day10,
+        part: Part::Part2,
+        input_hash: 0xdead_beef,
+        answer: answer.into(),
+        duration: Duration::from_micros(micros),
+        revision: "abc1234".into(),
+    }
+}
+
+#[test]
+fn test_record_round_trip() {
+    let record = test_record(1_670_000_000, "##..#\n#\\.##", 1234);
+
+    let line = record.to_line();
+    assert!(!line.contains('\n'));
+
+    let parsed = Record::from_line(&line).expect("should parse what we wrote");
+    assert!(parsed == record);
+}
+
+#[test]
+fn test_report_flags_changed_answer() {
+    let records = vec![
+        test_record(1_670_000_000, "42", 100),
+        test_record(1_670_000_100, "42", 50),
+        test_record(1_670_000_200, "41", 50),
+    ];
+
+    let output = report(&records, &Day::Day10);
+    assert!(output.contains("(0.50x previous time)"));
+    assert!(output.contains("ANSWER CHANGED (was '42'"));
+    assert!(output.contains("1 run(s) changed the answer"));
+
+    assert_eq!(report(&records, &Day::Day01), "no history for day01");
+}
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+    assert_eq!(format_timestamp(1_671_926_400 + 3_661), "2022-12-25 01:01:01");
+}
+
+#[test]
+fn test_hash_input_covers_unread_lines() {
+    let input = |lines: &[&str]| -> Box<dyn Iterator<Item = String>> {
+        Box::new(lines.iter().map(|l| l.to_string()).collect::<Vec<_>>().into_iter())
+    };
+
+    // a solver that only reads the first line still sees all of it
+    let (mut buf, hash) = hash_input(input(&["abc", "def"]));
+    assert_eq!(buf.next().as_deref(), Some("abc"));
+    let (_, other) = hash_input(input(&["abc", "xyz"]));
+    assert_ne!(hash, other);
+
+    let (buf, _) = hash_input(input(&["abc", "def"]));
+    assert_eq!(buf.collect::<Vec<_>>(), vec!["abc", "def"]);
+}
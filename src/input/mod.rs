@@ -0,0 +1,53 @@
+use std::io::BufRead;
+
+const BOM: char = '\u{feff}';
+
+/// cleans up input that was copied on Windows or through a browser, so the
+/// solvers only ever see plain lines:
+/// - strips a leading byte order mark from the first line
+/// - strips any trailing whitespace (including a `\r` left over from CRLF line endings)
+pub fn normalize(input: Box<dyn Iterator<Item = String>>) -> Box<dyn Iterator<Item = String>> {
+    Box::new(
+        input.enumerate().map(|(i, line)| {
+            let line = if i == 0 { line.trim_start_matches(BOM) } else { line.as_str() };
+            line.trim_end().to_string()
+        })
+    )
+}
+
+/// reads `reader` a line at a time, cleaning each one up with [`normalize`].
+pub fn normalized_lines(reader: Box<dyn BufRead>) -> Box<dyn Iterator<Item = String>> {
+    normalize(Box::new(reader.lines().map_while(Result::ok)))
+}
+
+/// drops a leading byte order mark from `reader`, for solvers reading it a byte at a time.
+pub fn skip_bom(reader: &mut dyn BufRead) {
+    let mut bom = [0; 3];
+    BOM.encode_utf8(&mut bom);
+
+    if reader.fill_buf().is_ok_and(|buf| buf.starts_with(&bom)) {
+        reader.consume(bom.len());
+    }
+}
+
+
+#[test]
+fn test_normalize_crlf() {
+    let lines = ["$ cd /\r", "$ ls\r", "dir a\r", ""]
+        .into_iter()
+        .map(String::from);
+
+    let output: Vec<String> = normalize(Box::new(lines)).collect();
+    assert_eq!(output, vec!["$ cd /", "$ ls", "dir a", ""]);
+}
+
+#[test]
+fn test_normalize_bom_and_whitespace() {
+    let lines = ["\u{feff}1000", "2000  \t", "    [D]    ", "\u{feff}3000"]
+        .into_iter()
+        .map(String::from);
+
+    let output: Vec<String> = normalize(Box::new(lines)).collect();
+    // only a leading BOM is stripped, anything later is left for the solver to reject.
+    assert_eq!(output, vec!["1000", "2000", "    [D]", "\u{feff}3000"]);
+}
@@ -0,0 +1,271 @@
+//! Inclusive integer intervals, and sets of them kept in normalized form.
+
+use std::fmt::Display;
+
+/// every integer from `start` to `end`, inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    /// the interval between `a` & `b`, whichever order they're given in.
+    pub fn new(a: i64, b: i64) -> Self {
+        Self { start: a.min(b), end: a.max(b) }
+    }
+
+    /// how many integers the interval covers (never 0, as both ends are included).
+    /// a `u128`, since `i64::MIN..=i64::MAX` covers one more than `u64` can count.
+    pub fn size(&self) -> u128 {
+        u128::from(self.start.abs_diff(self.end)) + 1
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// true if every value in `other` is also in `self`.
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        if self.overlaps(other) {
+            Some(Interval { start: self.start.max(other.start), end: self.end.min(other.end) })
+        } else {
+            None
+        }
+    }
+
+    /// true if the two can be merged into one interval (ie: they overlap, or `1-3` & `4-6`).
+    fn joins(&self, other: &Interval) -> bool {
+        self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1)
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}..={}", self.start, self.end))
+    }
+}
+
+
+/// A set of integers stored as sorted, disjoint & non-adjacent intervals.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sorts & merges `intervals` into normalized form.
+    fn normalize(mut intervals: Vec<Interval>) -> Self {
+        intervals.sort();
+
+        let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if last.joins(&interval) => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+
+        Self { intervals: merged }
+    }
+
+    /// adds `interval`, merging it with any intervals it overlaps or touches.
+    pub fn insert(&mut self, interval: Interval) {
+        // the intervals that join `interval` sit next to each other, so find where they start & end
+        let first = self.intervals.partition_point(|i| i.end.saturating_add(1) < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end.saturating_add(1));
+
+        if first == last {
+            self.intervals.insert(first, interval);
+            return;
+        }
+
+        let merged = Interval {
+            start: interval.start.min(self.intervals[first].start),
+            end: interval.end.max(self.intervals[last - 1].end),
+        };
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// how many integers are in the set.
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().map(|i| i.size()).sum()
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        // intervals are sorted, so find the last one starting at or before `value`
+        let i = self.intervals.partition_point(|i| i.start <= value);
+        i > 0 && self.intervals[i - 1].contains(value)
+    }
+
+    /// the smallest interval covering the whole set.
+    pub fn hull(&self) -> Option<Interval> {
+        Some(Interval { start: self.intervals.first()?.start, end: self.intervals.last()?.end })
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        Self::normalize(self.intervals.iter().chain(other.intervals.iter()).copied().collect())
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            result.extend(a.intersection(&b));
+
+            // drop whichever ends first, it can't overlap anything else
+            if a.end < b.end { i += 1 } else { j += 1 }
+        }
+
+        Self { intervals: result }
+    }
+
+    /// every value in `self` that isn't in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        match self.hull() {
+            Some(hull) => self.intersection(&other.gaps(hull)),
+            None => Self::new(),
+        }
+    }
+
+    /// the parts of `within` that the set doesn't cover.
+    pub fn gaps(&self, within: Interval) -> IntervalSet {
+        let mut result = Vec::new();
+        let mut next = within.start;
+
+        for interval in self.intervals.iter().filter(|i| i.overlaps(&within)) {
+            if interval.start > next {
+                result.push(Interval { start: next, end: interval.start - 1 });
+            }
+            match interval.end.checked_add(1) {
+                Some(after) => next = after,
+                // nothing comes after an interval that runs up to `i64::MAX`
+                None => return Self { intervals: result },
+            }
+        }
+        if next <= within.end {
+            result.push(Interval { start: next, end: within.end });
+        }
+
+        Self { intervals: result }
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = Interval>>(iter: T) -> Self {
+        Self::normalize(iter.into_iter().collect())
+    }
+}
+
+impl Display for IntervalSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self.intervals.iter().map(|i| i.to_string()).collect();
+        f.write_fmt(format_args!("{{{}}}", parts.join(", ")))
+    }
+}
+
+
+#[cfg(test)]
+fn set(intervals: &[(i64, i64)]) -> IntervalSet {
+    intervals.iter().map(|(a, b)| Interval::new(*a, *b)).collect()
+}
+
+#[test]
+fn test_interval() {
+    let a = Interval::new(6, 2);
+    assert_eq!(a, Interval { start: 2, end: 6 });
+    assert_eq!(a.size(), 5);
+    assert!(a.contains_interval(&Interval::new(3, 6)));
+    assert!(!a.contains_interval(&Interval::new(3, 7)));
+    assert!(a.overlaps(&Interval::new(6, 8)));
+    assert!(!a.overlaps(&Interval::new(7, 8)));
+    assert_eq!(a.intersection(&Interval::new(5, 9)), Some(Interval::new(5, 6)));
+}
+
+#[test]
+fn test_normalize() {
+    let s = set(&[(10, 12), (1, 3), (4, 5), (2, 2), (-4, -2)]);
+    assert_eq!(s.to_string(), "{-4..=-2, 1..=5, 10..=12}");
+    assert_eq!(s.len(), 11);
+    assert!(s.contains(5) && s.contains(-4) && !s.contains(6) && !s.contains(-5));
+
+    let mut s = s;
+    s.insert(Interval::new(6, 9));
+    assert_eq!(s.to_string(), "{-4..=-2, 1..=12}");
+}
+
+#[test]
+fn test_insert() {
+    let mut s = set(&[(0, 2), (6, 8), (12, 14), (20, 22)]);
+
+    s.insert(Interval::new(4, 4));
+    assert_eq!(s.to_string(), "{0..=2, 4..=4, 6..=8, 12..=14, 20..=22}");
+    // touches 4..=4 & spans up into 12..=14
+    s.insert(Interval::new(5, 13));
+    assert_eq!(s.to_string(), "{0..=2, 4..=14, 20..=22}");
+    s.insert(Interval::new(-10, -5));
+    s.insert(Interval::new(30, 31));
+    assert_eq!(s.to_string(), "{-10..=-5, 0..=2, 4..=14, 20..=22, 30..=31}");
+    s.insert(Interval::new(3, 3));
+    assert_eq!(s.to_string(), "{-10..=-5, 0..=14, 20..=22, 30..=31}");
+
+    // matches building the whole set at once
+    let mut built = IntervalSet::new();
+    for (a, b) in [(9, 12), (1, 3), (20, 25), (4, 4), (13, 19), (-3, -1)] {
+        built.insert(Interval::new(a, b));
+    }
+    assert_eq!(built, set(&[(9, 12), (1, 3), (20, 25), (4, 4), (13, 19), (-3, -1)]));
+
+    let mut edge = set(&[(i64::MAX - 1, i64::MAX)]);
+    edge.insert(Interval::new(i64::MAX - 5, i64::MAX - 2));
+    assert_eq!(edge, set(&[(i64::MAX - 5, i64::MAX)]));
+}
+
+#[test]
+fn test_size_of_everything() {
+    let everything = Interval::new(i64::MIN, i64::MAX);
+    assert_eq!(everything.size(), 1 << 64);
+    assert_eq!(set(&[(i64::MIN, -1), (1, i64::MAX)]).len(), (1 << 64) - 1);
+}
+
+#[test]
+fn test_set_operations() {
+    let a = set(&[(0, 10), (20, 30)]);
+    let b = set(&[(5, 25)]);
+
+    assert_eq!(a.union(&b), set(&[(0, 30)]));
+    assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+    assert_eq!(a.difference(&b), set(&[(0, 4), (26, 30)]));
+    assert_eq!(b.difference(&a), set(&[(11, 19)]));
+    assert_eq!(a.gaps(Interval::new(-5, 35)), set(&[(-5, -1), (11, 19), (31, 35)]));
+    assert!(a.gaps(Interval::new(2, 8)).is_empty());
+}
+
+#[test]
+fn test_gaps_at_max() {
+    let s = set(&[(0, 10), (20, i64::MAX)]);
+    assert_eq!(s.gaps(Interval::new(-5, i64::MAX)), set(&[(-5, -1), (11, 19)]));
+    assert!(s.gaps(Interval::new(i64::MAX, i64::MAX)).is_empty());
+}
@@ -11,17 +11,82 @@ mod day08;
 mod day09;
 mod day10;
 mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+mod day19;
+mod day20;
+mod day21;
+mod day22;
+mod day23;
 
-use std::fmt::Debug;
+pub mod cycle;
+pub mod ffi;
+pub mod grid;
+pub mod history;
+pub mod input;
+pub mod interval;
+pub mod math;
+pub mod parse;
+pub mod scale;
+pub mod search;
+pub mod validate;
+
+use std::{fmt::Debug, io::BufRead};
 
 use clap::ValueEnum;
 
+use validate::Issue;
+
 /// the main trait each 'day' module should implement to solve that day's input
 pub trait Solve {
     fn solve(&self, input: Box<dyn Iterator<Item = String>>, part: Part) -> String;
 }
 
-#[derive(Clone, ValueEnum)]
+/// solves a day incrementally, emitting results as the input arrives instead of waiting for the end of it.
+/// the input is read straight from `input` (a line at a time, or a byte at a time for day 6's single long line),
+/// and is always normalized (see [`input::normalize`]).
+/// only days where `can_stream` is true support this.
+pub trait SolveStream {
+    fn can_stream(&self) -> bool;
+    fn solve_stream(&self, input: Box<dyn BufRead>, part: Part, emit: &mut dyn FnMut(String));
+}
+
+/// solves a day, also describing how the answer was reached (ie: the path taken, or the final state).
+/// only days where `can_explain` is true support this.
+pub trait Explain {
+    fn can_explain(&self) -> bool;
+    /// returns `(answer, explanation)`.
+    /// `rows` limits how much of day 17's tower is drawn (other days draw everything).
+    fn explain(&self, input: Box<dyn Iterator<Item = String>>, part: Part, rows: Option<usize>) -> (String, String);
+}
+
+/// draws a day's state partway through, after a given number of rounds (ie: to compare with the puzzle's drawings).
+/// only days where `can_render` is true support this.
+pub trait Render {
+    fn can_render(&self) -> bool;
+    fn render(&self, input: Box<dyn Iterator<Item = String>>, rounds: usize) -> String;
+}
+
+/// solves a day step by step, without the shortcuts `solve` takes (ie: skipping ahead once a simulation repeats).
+/// slower, but useful for checking the shortcut's answer.
+/// only days where `can_brute_force` is true support this.
+pub trait SolveBruteForce {
+    fn can_brute_force(&self) -> bool;
+    fn solve_brute_force(&self, input: Box<dyn Iterator<Item = String>>, part: Part) -> String;
+}
+
+/// checks an input against a day's grammar without solving it,
+/// reporting every malformed line rather than stopping at the first.
+pub trait Validate {
+    fn validate(&self, input: Box<dyn Iterator<Item = String>>) -> Vec<Issue>;
+}
+
+#[derive(Clone, PartialEq, Eq, ValueEnum)]
 pub enum Day {
     Day01 = 1,
     Day02,
@@ -64,31 +129,148 @@ impl Solve for Day {
             Day::Day09 => day09::solve(input, part),
             Day::Day10 => day10::solve(input, part),
             Day::Day11 => day11::solve(input, part),
-            Day::Day12 => unimplemented!(), // day12::solve(input, part),
-            Day::Day13 => unimplemented!(), // day13::solve(input, part),
-            Day::Day14 => unimplemented!(), // day14::solve(input, part),
-            Day::Day15 => unimplemented!(), // day15::solve(input, part),
-            Day::Day16 => unimplemented!(), // day16::solve(input, part),
-            Day::Day17 => unimplemented!(), // day17::solve(input, part),
-            Day::Day18 => unimplemented!(), // day18::solve(input, part),
-            Day::Day19 => unimplemented!(), // day19::solve(input, part),
-            Day::Day20 => unimplemented!(), // day20::solve(input, part),
-            Day::Day21 => unimplemented!(), // day21::solve(input, part),
-            Day::Day22 => unimplemented!(), // day22::solve(input, part),
-            Day::Day23 => unimplemented!(), // day23::solve(input, part),
+            Day::Day12 => day12::solve(input, part),
+            Day::Day13 => day13::solve(input, part),
+            Day::Day14 => day14::solve(input, part),
+            Day::Day15 => day15::solve(input, part),
+            Day::Day16 => day16::solve(input, part),
+            Day::Day17 => day17::solve(input, part),
+            Day::Day18 => day18::solve(input, part),
+            Day::Day19 => day19::solve(input, part),
+            Day::Day20 => day20::solve(input, part),
+            Day::Day21 => day21::solve(input, part),
+            Day::Day22 => day22::solve(input, part),
+            Day::Day23 => day23::solve(input, part),
             Day::Day24 => unimplemented!(), // day24::solve(input, part),
             Day::Day25 => unimplemented!(), // day25::solve(input, part),
         }
     }    
 }
 
+impl SolveStream for Day {
+    fn can_stream(&self) -> bool {
+        matches!(self, Day::Day01 | Day::Day02 | Day::Day04 | Day::Day06)
+    }
+
+    fn solve_stream(&self, input: Box<dyn BufRead>, part: Part, emit: &mut dyn FnMut(String)) {
+        match self {
+            Day::Day01 => day01::solve_stream(input::normalized_lines(input), part, emit),
+            Day::Day02 => day02::solve_stream(input::normalized_lines(input), part, emit),
+            Day::Day04 => day04::solve_stream(input::normalized_lines(input), part, emit),
+            Day::Day06 => day06::solve_stream(input, part, emit),
+            _ => unreachable!("only days 1, 2, 4 & 6 can stream, check `can_stream` first"),
+        }
+    }
+}
+
+impl SolveBruteForce for Day {
+    fn can_brute_force(&self) -> bool {
+        matches!(self, Day::Day11)
+    }
+
+    fn solve_brute_force(&self, input: Box<dyn Iterator<Item = String>>, part: Part) -> String {
+        match self {
+            Day::Day11 => day11::solve_brute_force(input, part),
+            _ => unreachable!("only day 11 can be brute forced, check `can_brute_force` first"),
+        }
+    }
+}
+
+impl Explain for Day {
+    fn can_explain(&self) -> bool {
+        matches!(self, Day::Day12 | Day::Day14 | Day::Day16 | Day::Day17 | Day::Day19 | Day::Day23)
+    }
+
+    fn explain(&self, input: Box<dyn Iterator<Item = String>>, part: Part, rows: Option<usize>) -> (String, String) {
+        match self {
+            Day::Day12 => day12::explain(input, part),
+            Day::Day14 => day14::explain(input, part),
+            Day::Day16 => day16::explain(input, part),
+            Day::Day17 => day17::explain(input, part, rows.unwrap_or(day17::EXPLAIN_ROWS)),
+            Day::Day19 => day19::explain(input, part),
+            Day::Day23 => day23::explain(input, part),
+            _ => unreachable!("only days 12, 14, 16, 17, 19 & 23 can explain their answers, check `can_explain` first"),
+        }
+    }
+}
+
+impl Render for Day {
+    fn can_render(&self) -> bool {
+        matches!(self, Day::Day23)
+    }
+
+    fn render(&self, input: Box<dyn Iterator<Item = String>>, rounds: usize) -> String {
+        match self {
+            Day::Day23 => day23::render(input, rounds),
+            _ => unreachable!("only day 23 can be drawn after a given round, check `can_render` first"),
+        }
+    }
+}
+
+impl TryFrom<u32> for Day {
+    type Error = u32;
 
-#[derive(Clone, ValueEnum)]
+    /// maps a day number (1-25) to its `Day`
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        let index = usize::try_from(value).map_err(|_| value)?;
+        index.checked_sub(1)
+            .and_then(|i| Day::value_variants().get(i))
+            .cloned()
+            .ok_or(value)
+    }
+}
+
+impl Validate for Day {
+    fn validate(&self, input: Box<dyn Iterator<Item = String>>) -> Vec<Issue> {
+        match self {
+            Day::Day01 => day01::validate(input),
+            Day::Day02 => day02::validate(input),
+            Day::Day03 => day03::validate(input),
+            Day::Day04 => day04::validate(input),
+            Day::Day05 => day05::validate(input),
+            Day::Day06 => day06::validate(input),
+            Day::Day07 => day07::validate(input),
+            Day::Day08 => day08::validate(input),
+            Day::Day09 => day09::validate(input),
+            Day::Day10 => day10::validate(input),
+            Day::Day11 => day11::validate(input),
+            Day::Day12 => day12::validate(input),
+            Day::Day13 => day13::validate(input),
+            Day::Day14 => day14::validate(input),
+            Day::Day15 => day15::validate(input),
+            Day::Day16 => day16::validate(input),
+            Day::Day17 => day17::validate(input),
+            Day::Day18 => day18::validate(input),
+            Day::Day19 => day19::validate(input),
+            Day::Day20 => day20::validate(input),
+            Day::Day21 => day21::validate(input),
+            Day::Day22 => day22::validate(input),
+            Day::Day23 => day23::validate(input),
+            // nothing to check the input against yet
+            Day::Day24 | Day::Day25 => vec![Issue::new(1, "there's no solver for this day yet")],
+        }
+    }
+}
+
+
+#[derive(Clone, PartialEq, Eq, ValueEnum)]
 pub enum Part {
     Part1 = 1,
     Part2,
 }
 
+impl TryFrom<u32> for Part {
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Part::Part1),
+            2 => Ok(Part::Part2),
+            _ => Err(value),
+        }
+    }
+}
+
 impl Debug for Part {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -1,57 +1,378 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::{
-    path::PathBuf, 
+    path::{Path, PathBuf},
     io::{
-        BufReader, 
+        BufReader,
         BufRead,
         stdin, stdout, Write
-    }, 
-    fs::File
+    },
+    fs::{self, File},
+    panic::{self, AssertUnwindSafe},
+    process::ExitCode,
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
-use advent::{Day, Part, Solve};
+use advent::{Day, Explain, Part, Render, Solve, SolveBruteForce, SolveStream, Validate, input::normalize, history::{self, Record, HISTORY_FILE}};
 
 
 #[derive(Parser)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(value_enum, default_value_t = Day::Day01)]
     day: Day,
     #[arg(value_enum, default_value_t = Part::Part1)]
     part: Part,
-    
-    /// optional path to specify the input file to use.
-    #[arg(short,long)]
-    input: Option<PathBuf>,
+
+    /// optional path(s) to specify the input file(s) to use.
+    /// passing several files (or a directory) solves each one & labels the results.
+    #[arg(short, long, num_args = 1..)]
+    input: Vec<PathBuf>,
+
+    /// pass the input through as-is, skipping BOM/CRLF/trailing whitespace cleanup.
+    #[arg(long)]
+    raw: bool,
+
+    /// print results as the input arrives (ie: the running answer so far) instead of waiting for the end of it.
+    /// only supported for days 1, 2, 4 & 6, and the input is always cleaned up (so it can't be combined with --raw).
+    #[arg(long, conflicts_with_all = ["history", "raw"])]
+    stream: bool,
+
+    /// also print how the answer was reached (ie: the path taken, or the final state), for days that support it.
+    #[arg(long, conflicts_with_all = ["stream", "history"])]
+    explain: bool,
+
+    /// how many rows from the top of the tower --explain draws (day 17 only, defaults to 20).
+    #[arg(long, requires = "explain")]
+    rows: Option<usize>,
+
+    /// run every step of a long simulation instead of skipping ahead once it repeats (day 11 only).
+    /// much slower, but handy for double checking the faster answer.
+    #[arg(long, conflicts_with_all = ["stream", "explain"])]
+    brute_force: bool,
+
+    /// append a record of this run (input hash, answer, timing & git revision) to a history file.
+    #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = HISTORY_FILE)]
+    history: Option<PathBuf>,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// check an input against a day's grammar and report every malformed line, without solving it.
+    Validate {
+        #[arg(value_enum)]
+        day: Day,
 
-fn main() {
+        /// optional path to specify the input file to use.
+        #[arg(short,long)]
+        input: Option<PathBuf>,
+
+        /// pass the input through as-is, skipping BOM/CRLF/trailing whitespace cleanup.
+        #[arg(long)]
+        raw: bool,
+    },
+    /// time a day's solvers on generated inputs of growing size, and flag any that scale worse than expected.
+    Scale {
+        #[arg(value_enum)]
+        day: Day,
+
+        /// largest input size to generate, as a multiple of the base input (sizes double from 1x).
+        #[arg(long, default_value_t = 256)]
+        max_factor: usize,
+
+        /// how many times to run each size (the fastest run is kept).
+        #[arg(long, default_value_t = 5)]
+        repeats: usize,
+    },
+    /// draw a day's state after a given number of rounds (day 23 only).
+    Render {
+        #[arg(value_enum)]
+        day: Day,
+
+        /// how many rounds to run before drawing.
+        #[arg(long)]
+        rounds: usize,
+
+        /// optional path to specify the input file to use.
+        #[arg(short,long)]
+        input: Option<PathBuf>,
+
+        /// pass the input through as-is, skipping BOM/CRLF/trailing whitespace cleanup.
+        #[arg(long)]
+        raw: bool,
+    },
+    /// show how the answers & timings for a day have changed across recorded runs.
+    History {
+        #[arg(value_enum)]
+        day: Day,
+
+        /// history file to read.
+        #[arg(short, long, default_value = HISTORY_FILE)]
+        file: PathBuf,
+    },
+}
+
+
+fn main() -> ExitCode {
     let args = Cli::parse();
 
-    let buf = 
-        get_input_file(args.input)
-        .unwrap_or(get_stdinput());
+    match args.command {
+        Some(Command::Validate { day, input, raw }) => validate(day, input, raw),
+        Some(Command::Scale { day, max_factor, repeats }) => scale(day, max_factor, repeats),
+        Some(Command::Render { day, rounds, input, raw }) => render(day, rounds, input, raw),
+        Some(Command::History { day, file }) => show_history(day, file),
+        None => {
+            let is_batch = args.input.len() > 1 || args.input.iter().any(|p| p.is_dir());
+            if args.stream {
+                if is_batch || !args.day.can_stream() {
+                    eprintln!("--stream needs a single input, and a day that supports streaming (1, 2, 4 or 6)");
+                    return ExitCode::FAILURE;
+                }
+                return solve_stream(&args.day, args.part, args.input.into_iter().next());
+            }
+            if args.explain {
+                if is_batch || !args.day.can_explain() {
+                    eprintln!("--explain needs a single input, and a day that can explain its answers");
+                    return ExitCode::FAILURE;
+                }
+                if args.rows.is_some() && args.day != Day::Day17 {
+                    eprintln!("--rows only applies to day 17's tower, other days draw everything");
+                    return ExitCode::FAILURE;
+                }
+                let buf = get_input(args.input.into_iter().next(), args.raw);
+                let (answer, explanation) = args.day.explain(buf, args.part, args.rows);
+                let _ = stdout().write_all(format!("{}\n\n{}\n", explanation, answer).as_bytes());
+                return ExitCode::SUCCESS;
+            }
+            if args.brute_force && !args.day.can_brute_force() {
+                eprintln!("--brute-force needs a day with a shortcut to skip (11)");
+                return ExitCode::FAILURE;
+            }
+            if is_batch {
+                let paths = expand_inputs(args.input);
+                return solve_batch(&args.day, &args.part, paths, args.raw, args.brute_force, args.history.as_deref());
+            }
+
+            let buf = get_input(args.input.into_iter().next(), args.raw);
+            let (answer, status) = solve(&args.day, &args.part, buf, args.brute_force, args.history.as_deref());
+
+            let _ = stdout().write_all(format!("{}\n", answer).as_bytes());
+            status
+        },
+    }
+}
+
+/// solves a single input, recording the run to `history` if requested.
+fn solve(day: &Day, part: &Part, buf: Box<dyn Iterator<Item = String>>, brute_force: bool, history: Option<&Path>) -> (String, ExitCode) {
+    let run = |buf| if brute_force { day.solve_brute_force(buf, part.clone()) } else { day.solve(buf, part.clone()) };
+
+    let Some(history_file) = history else {
+        return (run(buf), ExitCode::SUCCESS);
+    };
+
+    let (buf, input_hash) = history::hash_input(buf);
+    let start = Instant::now();
+    let answer = run(buf);
+    let duration = start.elapsed();
+
+    let record = Record {
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+        day: day.clone(),
+        part: part.clone(),
+        input_hash,
+        answer,
+        duration,
+        revision: history::revision().into(),
+    };
+    let status = record_history(history_file, &record);
+
+    (record.answer, status)
+}
+
+/// prints each result as soon as the solver emits it.
+/// the input is handed over unbuffered by lines, so results don't wait for a whole line (or EOF) to arrive.
+fn solve_stream(day: &Day, part: Part, path: Option<PathBuf>) -> ExitCode {
+    let reader: Box<dyn BufRead> = match path {
+        Some(path) => match File::open(&path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(e) => {
+                eprintln!("failed to open '{}': {}", path.display(), e);
+                return ExitCode::FAILURE;
+            },
+        },
+        None => Box::new(stdin().lock()),
+    };
+
+    let mut out = stdout().lock();
+    day.solve_stream(reader, part, &mut |result| {
+        let _ = out.write_all(format!("{}\n", result).as_bytes());
+        let _ = out.flush();
+    });
+    ExitCode::SUCCESS
+}
+
+/// solves every input in turn, printing one labelled result per input.
+/// an input that can't be read (or that makes the solver panic) is reported, and the rest still run.
+fn solve_batch(day: &Day, part: &Part, paths: Vec<PathBuf>, raw: bool, brute_force: bool, history: Option<&Path>) -> ExitCode {
+    // we report panics next to their input instead of letting the default hook print them.
+    panic::set_hook(Box::new(|_| {}));
 
-    let answer = args.day.solve(buf, args.part);
+    let mut all_ok = true;
+    let mut out = stdout().lock();
+    for path in paths {
+        let result = File::open(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|file| {
+                let buf = read_lines(file, raw);
+                panic::catch_unwind(AssertUnwindSafe(|| solve(day, part, buf, brute_force, history)))
+                    .map_err(|payload| {
+                        payload.downcast_ref::<&str>().map(|s| s.to_string())
+                            .or_else(|| payload.downcast_ref::<String>().cloned())
+                            .unwrap_or_else(|| "solver panicked".into())
+                    })
+            });
 
-    let _ = stdout().write_all(format!("{}\n", answer).as_bytes());
-}   
+        let line = match result {
+            Ok((answer, status)) => {
+                all_ok &= status == ExitCode::SUCCESS;
+                // keep multi-line answers (ie: day10 part 2) lined up under their label
+                let sep = if answer.contains('\n') { "\n" } else { " " };
+                format!("{}:{}{}\n", path.display(), sep, answer)
+            },
+            Err(e) => {
+                all_ok = false;
+                format!("{}: error: {}\n", path.display(), e)
+            },
+        };
+        let _ = out.write_all(line.as_bytes());
+    }
+
+    let _ = panic::take_hook();
+    if all_ok { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+/// replaces any directories with the (sorted) files directly inside them.
+fn expand_inputs(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    paths.into_iter()
+        .flat_map(|path| {
+            let Ok(entries) = fs::read_dir(&path) else {
+                return vec![path];
+            };
+
+            let mut files: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|p| p.is_file())
+                .collect();
+            files.sort();
+            files
+        })
+        .collect()
+}
+
+fn record_history(path: &Path, record: &Record) -> ExitCode {
+    match history::append(path, record) {
+        Ok(None) => ExitCode::SUCCESS,
+        Ok(Some(prev)) => {
+            eprintln!("!! WARNING: the answer for this input changed !!");
+            eprintln!("!!   was: '{}' (revision {})", prev.answer, prev.revision);
+            eprintln!("!!   now: '{}' (revision {})", record.answer, record.revision);
+            ExitCode::FAILURE
+        },
+        Err(e) => {
+            eprintln!("failed to write history to '{}': {}", path.display(), e);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn scale(day: Day, max_factor: usize, repeats: usize) -> ExitCode {
+    let Some((lines, parts)) = advent::scale::measure(&day, max_factor, repeats) else {
+        eprintln!("no input generator for this day yet");
+        return ExitCode::FAILURE;
+    };
+
+    let _ = stdout().write_all(format!("{}\n", advent::scale::report(&lines, &parts)).as_bytes());
+
+    if parts.iter().any(|p| p.is_worse_than_expected()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn render(day: Day, rounds: usize, input: Option<PathBuf>, raw: bool) -> ExitCode {
+    if !day.can_render() {
+        eprintln!("render needs a day that can draw its state after a given round (23)");
+        return ExitCode::FAILURE;
+    }
+
+    let buf = get_input(input, raw);
+    let _ = stdout().write_all(format!("{}\n", day.render(buf, rounds)).as_bytes());
+    ExitCode::SUCCESS
+}
+
+fn show_history(day: Day, path: PathBuf) -> ExitCode {
+    match history::load(&path) {
+        Ok(records) => {
+            let _ = stdout().write_all(format!("{}\n", history::report(&records, &day)).as_bytes());
+            ExitCode::SUCCESS
+        },
+        Err(e) => {
+            eprintln!("failed to read history from '{}': {}", path.display(), e);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn validate(day: Day, input: Option<PathBuf>, raw: bool) -> ExitCode {
+    let buf = get_input(input, raw);
+
+    let issues = day.validate(buf);
+    if issues.is_empty() {
+        let _ = stdout().write_all(b"ok\n");
+        return ExitCode::SUCCESS;
+    }
+
+    let mut out = stdout().lock();
+    for issue in issues {
+        let _ = out.write_all(format!("{}\n", issue).as_bytes());
+    }
+    ExitCode::FAILURE
+}
+
+fn get_input(path: Option<PathBuf>, raw: bool) -> Box<dyn Iterator<Item = String>> {
+    let buf =
+        get_input_file(path)
+        .unwrap_or_else(get_stdinput);
+
+    if raw { buf } else { normalize(buf) }
+}
+
+fn read_lines(file: File, raw: bool) -> Box<dyn Iterator<Item = String>> {
+    let buf = file_lines(file);
+
+    if raw { buf } else { normalize(buf) }
+}
 
 fn get_input_file(path: Option<PathBuf>) -> Option<Box<dyn Iterator<Item = String>>> {
 
     let path = path?;
-    Some(
-        Box::new(
-            BufReader::new(File::open(path).ok()?)
-            .lines()
-            .filter_map(|l| l.ok() )
-        )
+    Some(file_lines(File::open(path).ok()?))
+}
+
+fn file_lines(file: File) -> Box<dyn Iterator<Item = String>> {
+    Box::new(
+        BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
     )
 }
 
 fn get_stdinput() -> Box<dyn Iterator<Item = String>> {
     Box::new(
-        stdin().lines().filter_map(|l| l.ok() )
+        stdin().lines().map_while(Result::ok)
     )
-}
\ No newline at end of file
+}
@@ -0,0 +1,321 @@
+//! A signed arbitrary-precision integer, for answers that outgrow `i128`.
+
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+use super::ArithmeticError;
+
+/// limbs are base 2^32, least significant first, with no trailing zero limbs.
+/// zero is an empty magnitude, and is never negative.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> Self {
+        Self { negative: false, magnitude: self.magnitude.clone() }
+    }
+
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        let negative = negative && !magnitude.is_empty();
+        Self { negative, magnitude }
+    }
+
+    /// truncating division (like the primitive types), with the remainder taking the sign of `self`.
+    pub fn div_rem(&self, rhs: &BigInt) -> Result<(BigInt, BigInt), ArithmeticError> {
+        if rhs.is_zero() {
+            return Err(ArithmeticError::DivideByZero { expr: format!("{} / {}", self, rhs) });
+        }
+
+        let (q, r) = div_rem_magnitude(&self.magnitude, &rhs.magnitude);
+        Ok((
+            Self::from_parts(self.negative != rhs.negative, q),
+            Self::from_parts(self.negative, r),
+        ))
+    }
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0_u64;
+    for i in 0..a.len().max(b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+/// `a - b`, where `a >= b`.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0_i64;
+    for (i, limb) in a.iter().enumerate() {
+        let mut diff = *limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = if diff < 0 { diff += 1 << 32; 1 } else { 0 };
+        result.push(diff as u32);
+    }
+    result
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0_u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0_u64;
+        for (j, y) in b.iter().enumerate() {
+            let cur = result[i + j] as u64 + *x as u64 * *y as u64 + carry;
+            result[i + j] = cur as u32;
+            carry = cur >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    result
+}
+
+/// schoolbook long division, one bit at a time.
+fn div_rem_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![0_u32; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+
+    for bit in (0..a.len() * 32).rev() {
+        // remainder = remainder << 1 | next bit of a
+        let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+        for limb in remainder.iter_mut() {
+            let next = *limb >> 31;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        if carry > 0 {
+            remainder.push(carry);
+        }
+
+        if cmp_magnitude(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitude(&remainder, b);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+
+    (quotient, remainder)
+}
+
+/// divides in place by a small value, returning the remainder.
+fn div_small(magnitude: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut rem = 0_u64;
+    for limb in magnitude.iter_mut().rev() {
+        let cur = (rem << 32) | *limb as u64;
+        *limb = (cur / divisor as u64) as u32;
+        rem = cur % divisor as u64;
+    }
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    rem as u32
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        let mut n = value.unsigned_abs();
+        let mut magnitude = Vec::new();
+        while n > 0 {
+            magnitude.push(n as u32);
+            n >>= 32;
+        }
+        Self::from_parts(value < 0, magnitude)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        Self::from(value as i128)
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(value: u64) -> Self {
+        Self::from(value as i128)
+    }
+}
+
+impl TryFrom<&BigInt> for i128 {
+    type Error = ArithmeticError;
+
+    fn try_from(value: &BigInt) -> Result<Self, Self::Error> {
+        let too_big = || ArithmeticError::Overflow { expr: format!("{} as i128", value) };
+        if value.magnitude.len() > 4 {
+            return Err(too_big());
+        }
+
+        let n = value.magnitude.iter().rev().fold(0_u128, |acc, limb| (acc << 32) | *limb as u128);
+        if value.negative {
+            0_i128.checked_sub_unsigned(n).ok_or_else(too_big)
+        } else {
+            i128::try_from(n).map_err(|_| too_big())
+        }
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("'{}' isn't an integer", s));
+        }
+
+        let ten = BigInt::from(10_i64);
+        let value = digits.bytes()
+            .fold(BigInt::zero(), |acc, b| &(&acc * &ten) + &BigInt::from((b - b'0') as i64));
+        Ok(if negative { -value } else { value })
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return f.write_str("0");
+        }
+
+        // peel off 9 decimal digits at a time
+        let mut magnitude = self.magnitude.clone();
+        let mut chunks: Vec<u32> = Vec::new();
+        while !magnitude.is_empty() {
+            chunks.push(div_small(&mut magnitude, 1_000_000_000));
+        }
+
+        let mut out = String::new();
+        if self.negative {
+            out.push('-');
+        }
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            out.push_str(&first.to_string());
+        }
+        for chunk in chunks {
+            out.push_str(&format!("{:09}", chunk));
+        }
+        f.write_str(&out)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => cmp_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        Self::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, add_magnitude(&self.magnitude, &rhs.magnitude));
+        }
+
+        // opposite signs, so take the smaller magnitude from the larger
+        match cmp_magnitude(&self.magnitude, &rhs.magnitude) {
+            Ordering::Less => BigInt::from_parts(rhs.negative, sub_magnitude(&rhs.magnitude, &self.magnitude)),
+            _ => BigInt::from_parts(self.negative, sub_magnitude(&self.magnitude, &rhs.magnitude)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + &(-rhs.clone())
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        BigInt::from_parts(self.negative != rhs.negative, mul_magnitude(&self.magnitude, &rhs.magnitude))
+    }
+}
+
+
+#[test]
+fn test_round_trip() {
+    for s in ["0", "1", "-1", "4294967296", "-18446744073709551617", "123456789012345678901234567890"] {
+        assert_eq!(s.parse::<BigInt>().unwrap().to_string(), s);
+    }
+    assert!("12a".parse::<BigInt>().is_err());
+    assert!("-".parse::<BigInt>().is_err());
+    assert_eq!("-0".parse::<BigInt>().unwrap(), BigInt::zero());
+}
+
+#[test]
+fn test_arithmetic() {
+    let a: BigInt = "123456789012345678901234567890".parse().unwrap();
+    let b: BigInt = "-987654321098765432109876543210".parse().unwrap();
+
+    assert_eq!((&a + &b).to_string(), "-864197532086419753208641975320");
+    assert_eq!((&a - &b).to_string(), "1111111110111111111011111111100");
+    assert_eq!((&a * &b).to_string(), "-121932631137021795226185032733622923332237463801111263526900");
+
+    let (q, r) = b.div_rem(&a).unwrap();
+    assert_eq!((q.to_string(), r.to_string()), ("-8".into(), "-9000000000900000000090".into()));
+    assert_eq!(&(&q * &a) + &r, b);
+    assert!(a.div_rem(&BigInt::zero()).is_err());
+
+    assert!(b < a && -a.clone() > b);
+    assert_eq!(i128::try_from(&BigInt::from(i128::MIN)), Ok(i128::MIN));
+    assert!(i128::try_from(&(&a * &a)).is_err());
+}
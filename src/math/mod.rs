@@ -0,0 +1,203 @@
+//! Number theory helpers, modular arithmetic & overflow checked operations.
+//!
+//! Puzzle answers regularly outgrow `u64` (or get close), and a release build
+//! wraps silently, so solvers should use the checked ops here and report the error.
+
+use std::fmt::Display;
+
+mod operand;
+mod rational;
+pub use operand::Operand;
+pub use rational::Rational;
+
+#[cfg(feature = "bigint")]
+mod bigint;
+#[cfg(feature = "bigint")]
+pub use bigint::BigInt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArithmeticError {
+    /// the result of `expr` doesn't fit in the type it was calculated in.
+    Overflow { expr: String },
+    DivideByZero { expr: String },
+    /// `value` has no inverse modulo `modulus` (they aren't coprime).
+    NoInverse { value: u64, modulus: u64 },
+}
+
+impl Display for ArithmeticError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArithmeticError::Overflow { expr } => f.write_fmt(format_args!("arithmetic overflow in '{}'", expr)),
+            ArithmeticError::DivideByZero { expr } => f.write_fmt(format_args!("division by zero in '{}'", expr)),
+            ArithmeticError::NoInverse { value, modulus } =>
+                f.write_fmt(format_args!("{} has no inverse modulo {}", value, modulus)),
+        }
+    }
+}
+
+impl std::error::Error for ArithmeticError {}
+
+
+/// Arithmetic that reports overflow instead of wrapping, or clamps at the type's limits.
+/// (the std methods do the same, but aren't available through a trait for generic code)
+pub trait Checked: Sized + Copy + Display {
+    fn add_checked(self, rhs: Self) -> Result<Self, ArithmeticError>;
+    fn sub_checked(self, rhs: Self) -> Result<Self, ArithmeticError>;
+    fn mul_checked(self, rhs: Self) -> Result<Self, ArithmeticError>;
+    fn div_checked(self, rhs: Self) -> Result<Self, ArithmeticError>;
+
+    fn add_saturating(self, rhs: Self) -> Self;
+    fn sub_saturating(self, rhs: Self) -> Self;
+    fn mul_saturating(self, rhs: Self) -> Self;
+}
+
+macro_rules! checked_int {
+    ($($t:ty),*) => {$(
+        impl Checked for $t {
+            fn add_checked(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                self.checked_add(rhs).ok_or_else(|| overflow(self, '+', rhs))
+            }
+
+            fn sub_checked(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                self.checked_sub(rhs).ok_or_else(|| overflow(self, '-', rhs))
+            }
+
+            fn mul_checked(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                self.checked_mul(rhs).ok_or_else(|| overflow(self, '*', rhs))
+            }
+
+            fn div_checked(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                if rhs == 0 {
+                    return Err(ArithmeticError::DivideByZero { expr: format!("{} / {}", self, rhs) });
+                }
+                // only MIN / -1 can still overflow
+                self.checked_div(rhs).ok_or_else(|| overflow(self, '/', rhs))
+            }
+
+            fn add_saturating(self, rhs: Self) -> Self {
+                self.saturating_add(rhs)
+            }
+
+            fn sub_saturating(self, rhs: Self) -> Self {
+                self.saturating_sub(rhs)
+            }
+
+            fn mul_saturating(self, rhs: Self) -> Self {
+                self.saturating_mul(rhs)
+            }
+        }
+    )*};
+}
+
+checked_int!(u32, u64, u128, usize, i32, i64, i128, isize);
+
+fn overflow<T: Display>(left: T, op: char, right: T) -> ArithmeticError {
+    ArithmeticError::Overflow { expr: format!("{} {} {}", left, op, right) }
+}
+
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// the least common multiple, or an error if it doesn't fit in a `u64`.
+pub fn lcm(a: u64, b: u64) -> Result<u64, ArithmeticError> {
+    if a == 0 || b == 0 {
+        return Ok(0);
+    }
+    (a / gcd(a, b)).mul_checked(b)
+}
+
+/// the least common multiple of every value (1 for no values).
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> Result<u64, ArithmeticError> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+/// `(g, x, y)` where `g = gcd(a, b)` and `a*x + b*y = g`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+        (g, y, x - a.div_euclid(b) * y)
+    }
+}
+
+
+// modular arithmetic, every result is in `0..m` and intermediate values can't overflow
+
+pub fn mod_add(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 + b as u128) % m as u128) as u64
+}
+
+pub fn mod_sub(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 + m as u128 - (b % m) as u128) % m as u128) as u64
+}
+
+pub fn mod_mul(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+pub fn mod_pow(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mod_mul(result, base, m);
+        }
+        base = mod_mul(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// `x` such that `a * x = 1 (mod m)`.
+pub fn mod_inv(a: u64, m: u64) -> Result<u64, ArithmeticError> {
+    let (g, x, _) = extended_gcd(a as i128, m as i128);
+    if g != 1 {
+        return Err(ArithmeticError::NoInverse { value: a, modulus: m });
+    }
+    Ok(x.rem_euclid(m as i128) as u64)
+}
+
+
+#[test]
+fn test_gcd_lcm() {
+    assert_eq!(gcd(12, 18), 6);
+    assert_eq!(gcd(7, 0), 7);
+    assert_eq!(lcm(4, 6), Ok(12));
+    // not just the product, which is what day11 used to assume
+    assert_eq!(lcm_all([4, 6, 10]), Ok(60));
+    assert_eq!(lcm_all([23, 19, 13, 17]), Ok(96577));
+    assert!(matches!(lcm(u64::MAX, u64::MAX - 1), Err(ArithmeticError::Overflow { .. })));
+}
+
+#[test]
+fn test_checked() {
+    assert_eq!(2_u64.sub_checked(1), Ok(1));
+    assert_eq!(1_u64.sub_checked(2), Err(ArithmeticError::Overflow { expr: "1 - 2".into() }));
+    assert!(u64::MAX.mul_checked(2).is_err());
+    assert!(i64::MIN.div_checked(-1).is_err());
+    assert_eq!(5_i32.div_checked(0), Err(ArithmeticError::DivideByZero { expr: "5 / 0".into() }));
+
+    assert_eq!(u64::MAX.add_saturating(1), u64::MAX);
+    assert_eq!(1_u64.sub_saturating(2), 0);
+    assert_eq!(i64::MIN.mul_saturating(2), i64::MIN);
+}
+
+#[test]
+fn test_modular() {
+    let m = 1_000_000_007;
+    assert_eq!(mod_add(m - 1, 5, m), 4);
+    assert_eq!(mod_sub(3, 5, m), m - 2);
+    assert_eq!(mod_mul(u64::MAX, u64::MAX, m), ((u64::MAX as u128 * u64::MAX as u128) % m as u128) as u64);
+    assert_eq!(mod_pow(2, 10, 1000), 24);
+    assert_eq!(mod_pow(5, 0, 1), 0);
+
+    let inv = mod_inv(3, 11).unwrap();
+    assert_eq!(mod_mul(3, inv, 11), 1);
+    assert_eq!(mod_inv(4, 8), Err(ArithmeticError::NoInverse { value: 4, modulus: 8 }));
+}
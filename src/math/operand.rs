@@ -0,0 +1,95 @@
+//! The four arithmetic operators the puzzles write out as `a op b`.
+
+use std::fmt::Display;
+
+use super::{self as math, ArithmeticError, Checked};
+use crate::parse::{Cursor, ParseResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Add,
+    Sub,
+    Multi,
+    Divide,
+}
+
+impl Operand {
+    const SYMBOLS: [(&'static str, Operand); 4] = [("+", Self::Add), ("-", Self::Sub), ("*", Self::Multi), ("/", Self::Divide)];
+
+    pub fn parse(c: &mut Cursor) -> ParseResult<Self> {
+        c.one_of(&Operand::SYMBOLS)
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        Operand::SYMBOLS[*self as usize].0
+    }
+
+    pub fn do_op<T: Checked>(&self, left: T, right: T) -> Result<T, ArithmeticError> {
+        match *self {
+            Self::Add => left.add_checked(right),
+            Self::Sub => left.sub_checked(right),
+            Self::Multi => left.mul_checked(right),
+            Self::Divide => left.div_checked(right),
+        }
+    }
+
+    /// the op modulo `m`, which can't overflow.
+    /// division has no meaning once values are reduced, so that gives `None`.
+    pub fn do_op_mod(&self, left: u64, right: u64, m: u64) -> Option<u64> {
+        match *self {
+            Self::Add => Some(math::mod_add(left, right, m)),
+            Self::Sub => Some(math::mod_sub(left, right, m)),
+            Self::Multi => Some(math::mod_mul(left, right, m)),
+            Self::Divide => None,
+        }
+    }
+
+    /// the `left` that makes `left op right = result`.
+    pub fn solve_left<T: Checked>(&self, right: T, result: T) -> Result<T, ArithmeticError> {
+        match *self {
+            Self::Add => result.sub_checked(right),
+            Self::Sub => result.add_checked(right),
+            Self::Multi => result.div_checked(right),
+            Self::Divide => result.mul_checked(right),
+        }
+    }
+
+    /// the `right` that makes `left op right = result`.
+    pub fn solve_right<T: Checked>(&self, left: T, result: T) -> Result<T, ArithmeticError> {
+        match *self {
+            Self::Add => result.sub_checked(left),
+            Self::Sub => left.sub_checked(result),
+            Self::Multi => result.div_checked(left),
+            Self::Divide => left.div_checked(result),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+
+#[test]
+fn test_operand() {
+    use crate::parse;
+
+    let ops: Vec<Operand> = ["+", "-", "*", "/"].iter()
+        .map(|s| parse::parse_line(s, 1, Operand::parse).unwrap())
+        .collect();
+    assert_eq!(ops, vec![Operand::Add, Operand::Sub, Operand::Multi, Operand::Divide]);
+    assert_eq!(Operand::Divide.to_string(), "/");
+
+    // each solve undoes the op, from either side
+    for op in ops {
+        let (left, right) = (84_i64, 4_i64);
+        let result = op.do_op(left, right).unwrap();
+        assert_eq!(op.solve_left(right, result), Ok(left), "{}", op);
+        assert_eq!(op.solve_right(left, result), Ok(right), "{}", op);
+    }
+
+    assert_eq!(Operand::Multi.do_op_mod(7, 5, 6), Some(5));
+    assert_eq!(Operand::Divide.do_op_mod(7, 5, 6), None);
+}
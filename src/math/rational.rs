@@ -0,0 +1,188 @@
+//! An exact fraction, for puzzles where division mustn't round.
+
+use std::{cmp::Ordering, fmt::Display};
+
+use super::{ArithmeticError, Checked};
+
+/// always in lowest terms, with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    // only 2^127 doesn't fit (ie: gcd(MIN, MIN)), so that just isn't reduced
+    i128::try_from(a).unwrap_or(1)
+}
+
+impl Rational {
+    pub fn new(numer: i128, denom: i128) -> Result<Self, ArithmeticError> {
+        if denom == 0 {
+            return Err(ArithmeticError::DivideByZero { expr: format!("{} / {}", numer, denom) });
+        }
+
+        let g = gcd(numer, denom).max(1);
+        let (numer, denom) = (numer / g, denom / g);
+        if denom < 0 {
+            let overflow = || ArithmeticError::Overflow { expr: format!("-{} / -{}", numer, denom) };
+            Ok(Self { numer: numer.checked_neg().ok_or_else(overflow)?, denom: denom.checked_neg().ok_or_else(overflow)? })
+        } else {
+            Ok(Self { numer, denom })
+        }
+    }
+
+    pub fn from_integer(n: i128) -> Self {
+        Self { numer: n, denom: 1 }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.denom
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == 1
+    }
+
+    /// the value as an integer, if it is one.
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.numer)
+    }
+
+    /// `a/b + c/d` as `(a*(l/b) + c*(l/d)) / l`, with `l` the lcm of the denominators.
+    fn add_fractions(self, rhs: Self, op: char) -> Result<Self, ArithmeticError> {
+        let overflow = || ArithmeticError::Overflow { expr: format!("{} {} {}", self, op, rhs) };
+
+        let g = gcd(self.denom, rhs.denom);
+        let denom = (self.denom / g).checked_mul(rhs.denom).ok_or_else(overflow)?;
+        let left = self.numer.checked_mul(denom / self.denom).ok_or_else(overflow)?;
+        let mut right = rhs.numer.checked_mul(denom / rhs.denom).ok_or_else(overflow)?;
+        if op == '-' {
+            right = right.checked_neg().ok_or_else(overflow)?;
+        }
+
+        Rational::new(left.checked_add(right).ok_or_else(overflow)?, denom)
+    }
+
+    /// `(a/b) * (c/d)`, cross reducing first so the products stay as small as they can.
+    fn mul_fractions(self, rhs: Self, op: char) -> Result<Self, ArithmeticError> {
+        let overflow = || ArithmeticError::Overflow { expr: format!("{} {} {}", self, op, rhs) };
+
+        let g1 = gcd(self.numer, rhs.denom).max(1);
+        let g2 = gcd(rhs.numer, self.denom).max(1);
+        let numer = (self.numer / g1).checked_mul(rhs.numer / g2).ok_or_else(overflow)?;
+        let denom = (self.denom / g2).checked_mul(rhs.denom / g1).ok_or_else(overflow)?;
+
+        Rational::new(numer, denom)
+    }
+
+    /// `result`, or the limit it overflowed past.
+    fn saturate(result: Result<Self, ArithmeticError>, positive: bool) -> Self {
+        result.unwrap_or_else(|_| Rational::from_integer(if positive { i128::MAX } else { -i128::MAX }))
+    }
+
+    fn is_positive(&self) -> bool {
+        self.numer > 0
+    }
+}
+
+/// saturating ops clamp to `±i128::MAX`, which is as far as the numerator reaches.
+impl Checked for Rational {
+    fn add_checked(self, rhs: Self) -> Result<Self, ArithmeticError> {
+        self.add_fractions(rhs, '+')
+    }
+
+    fn sub_checked(self, rhs: Self) -> Result<Self, ArithmeticError> {
+        self.add_fractions(rhs, '-')
+    }
+
+    fn mul_checked(self, rhs: Self) -> Result<Self, ArithmeticError> {
+        self.mul_fractions(rhs, '*')
+    }
+
+    fn div_checked(self, rhs: Self) -> Result<Self, ArithmeticError> {
+        if rhs.numer == 0 {
+            return Err(ArithmeticError::DivideByZero { expr: format!("{} / {}", self, rhs) });
+        }
+        self.mul_fractions(Rational::new(rhs.denom, rhs.numer)?, '/')
+    }
+
+    fn add_saturating(self, rhs: Self) -> Self {
+        Rational::saturate(self.add_checked(rhs), self.is_positive())
+    }
+
+    fn sub_saturating(self, rhs: Self) -> Self {
+        Rational::saturate(self.sub_checked(rhs), self.is_positive())
+    }
+
+    fn mul_saturating(self, rhs: Self) -> Self {
+        Rational::saturate(self.mul_checked(rhs), self.is_positive() == rhs.is_positive())
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Rational::from_integer(n as i128)
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // denominators are positive, so cross multiplying keeps the order
+        // (falling back to floats if that overflows)
+        match (self.numer.checked_mul(other.denom), other.numer.checked_mul(self.denom)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => (self.numer as f64 / self.denom as f64).total_cmp(&(other.numer as f64 / other.denom as f64)),
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            f.write_fmt(format_args!("{}", self.numer))
+        } else {
+            f.write_fmt(format_args!("{}/{}", self.numer, self.denom))
+        }
+    }
+}
+
+
+#[test]
+fn test_rational() {
+    let r = |n, d| Rational::new(n, d).unwrap();
+
+    assert_eq!(r(6, -4), r(-3, 2));
+    assert_eq!(r(-3, 2).to_string(), "-3/2");
+    assert_eq!(r(4, 2).to_integer(), Some(2));
+    assert!(Rational::new(1, 0).is_err());
+
+    assert_eq!(r(1, 2).add_checked(r(1, 3)), Ok(r(5, 6)));
+    assert_eq!(r(1, 2).sub_checked(r(3, 4)), Ok(r(-1, 4)));
+    assert_eq!(r(2, 3).mul_checked(r(9, 4)), Ok(r(3, 2)));
+    assert_eq!(r(1, 3).div_checked(r(-2, 3)), Ok(r(-1, 2)));
+    assert!(matches!(r(1, 3).div_checked(r(0, 1)), Err(ArithmeticError::DivideByZero { .. })));
+
+    // 7 / 2 isn't silently truncated to 3
+    assert_eq!(Rational::from(7).div_checked(Rational::from(2)).unwrap().to_integer(), None);
+
+    let big = Rational::from_integer(i128::MAX);
+    assert!(matches!(big.add_checked(r(1, 1)), Err(ArithmeticError::Overflow { .. })));
+    assert_eq!(big.add_saturating(r(1, 1)), big);
+    assert!(r(1, 3) < r(1, 2));
+    assert!(r(-1, 2) < r(-1, 3));
+}
@@ -0,0 +1,331 @@
+//! Small parser combinators for the puzzle inputs.
+//!
+//! A `Cursor` walks a single line, and each combinator either consumes what it
+//! expects or fails with a `ParseError` pointing at the line & column it stopped at.
+//! Days describe their grammar as functions over a cursor, so `solve` & `validate`
+//! share one definition of what a valid line is.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::validate::Issue;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column (in characters).
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("line {}, column {}: {}", self.line, self.column, self.message))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for Issue {
+    fn from(e: ParseError) -> Self {
+        Issue::new(e.line, format!("column {}: {}", e.column, e.message))
+    }
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+
+/// A position within one line of input.
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    text: &'a str,
+    /// byte offset into `text`.
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// a cursor at the start of `text`, which is line number `line` (1-based) of the input.
+    pub fn new(text: &'a str, line: usize) -> Self {
+        Self { text, pos: 0, line }
+    }
+
+    /// everything not consumed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.text.len()
+    }
+
+    /// an error at the current position.
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.text[..self.pos].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    /// describes what's at the current position, for error messages.
+    fn found(&self) -> String {
+        match self.rest() {
+            "" => "end of line".into(),
+            rest => format!("'{}'", rest),
+        }
+    }
+
+    pub fn literal(&mut self, expected: &str) -> ParseResult<()> {
+        if self.try_literal(expected) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}', found {}", expected, self.found())))
+        }
+    }
+
+    /// consumes `expected` if it's next, without failing if it isn't.
+    pub fn try_literal(&mut self, expected: &str) -> bool {
+        if self.rest().starts_with(expected) {
+            self.pos += expected.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// skips any spaces or tabs, returning how many were skipped.
+    pub fn whitespace(&mut self) -> usize {
+        let skipped = self.rest().len() - self.rest().trim_start_matches([' ', '\t']).len();
+        self.pos += skipped;
+        skipped
+    }
+
+    /// an integer, with an optional leading `-` (which `T` may reject).
+    pub fn integer<T: FromStr>(&mut self) -> ParseResult<T> {
+        let rest = self.rest();
+        let sign = if rest.starts_with('-') { 1 } else { 0 };
+        let digits = rest[sign..].bytes().take_while(|b| b.is_ascii_digit()).count();
+        if digits == 0 {
+            return Err(self.error(format!("expected an integer, found {}", self.found())));
+        }
+
+        let value = rest[..sign + digits].parse()
+            .map_err(|_| self.error(format!("'{}' is out of range", &rest[..sign + digits])))?;
+        self.pos += sign + digits;
+        Ok(value)
+    }
+
+    /// a (non-empty) run of characters up to the next whitespace or the end of the line.
+    pub fn word(&mut self) -> ParseResult<&'a str> {
+        let rest = self.rest();
+        let len = rest.find([' ', '\t']).unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error(format!("expected a word, found {}", self.found())));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    pub fn char(&mut self) -> ParseResult<char> {
+        let c = self.rest().chars().next()
+            .ok_or_else(|| self.error("expected a character, found end of line"))?;
+        self.pos += c.len_utf8();
+        Ok(c)
+    }
+
+    /// the value paired with the first literal in `options` that's next.
+    pub fn one_of<T: Clone>(&mut self, options: &[(&str, T)]) -> ParseResult<T> {
+        for (literal, value) in options {
+            if self.try_literal(literal) {
+                return Ok(value.clone());
+            }
+        }
+
+        let expected: Vec<String> = options.iter().map(|(l, _)| format!("'{}'", l)).collect();
+        Err(self.error(format!("expected one of {}, found {}", expected.join(", "), self.found())))
+    }
+
+    /// one or more `item`s, with `separator` between each.
+    pub fn separated<T>(&mut self, separator: &str, mut item: impl FnMut(&mut Self) -> ParseResult<T>) -> ParseResult<Vec<T>> {
+        let mut items = vec![item(self)?];
+        while self.try_literal(separator) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// runs `f`, putting the cursor back where it was if it fails.
+    pub fn attempt<T>(&mut self, f: impl FnOnce(&mut Self) -> ParseResult<T>) -> Option<T> {
+        let start = self.pos;
+        match f(self) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                self.pos = start;
+                None
+            },
+        }
+    }
+
+    /// fails unless the whole line has been consumed.
+    pub fn end(&self) -> ParseResult<()> {
+        if self.is_at_end() {
+            Ok(())
+        } else {
+            Err(self.error(format!("unexpected {}", self.found())))
+        }
+    }
+}
+
+
+/// parses all of `text` (line `line` of the input) with `f`.
+pub fn parse_line<'a, T>(text: &'a str, line: usize, f: impl FnOnce(&mut Cursor<'a>) -> ParseResult<T>) -> ParseResult<T> {
+    let mut cursor = Cursor::new(text, line);
+    let value = f(&mut cursor)?;
+    cursor.end()?;
+    Ok(value)
+}
+
+/// parses every non-blank line of `input` with `f`.
+pub fn lines<T, F>(input: impl Iterator<Item = String>, f: F) -> impl Iterator<Item = ParseResult<T>>
+where
+    F: for<'a> FnMut(&mut Cursor<'a>) -> ParseResult<T>,
+{
+    lines_from(input, 1, f)
+}
+
+/// like [`lines`], for input that's already been partly read: its first line is line `first_line` of the file.
+pub fn lines_from<T, F>(input: impl Iterator<Item = String>, first_line: usize, mut f: F) -> impl Iterator<Item = ParseResult<T>>
+where
+    F: for<'a> FnMut(&mut Cursor<'a>) -> ParseResult<T>,
+{
+    input.enumerate()
+        .filter(|(_, text)| !text.is_empty())
+        .map(move |(i, text)| parse_line(&text, first_line + i, &mut f))
+}
+
+/// like [`lines`], but quietly drops any line that doesn't parse.
+/// for solvers that have always skipped lines they don't understand, `validate` is what reports them.
+pub fn valid_lines<T, F>(input: impl Iterator<Item = String>, f: F) -> impl Iterator<Item = T>
+where
+    F: for<'a> FnMut(&mut Cursor<'a>) -> ParseResult<T>,
+{
+    lines(input, f).filter_map(Result::ok)
+}
+
+/// parses every line of `input` (blank ones included) with `f`, reporting each failure as an `Issue`.
+pub(crate) fn validate_lines<T, F>(input: impl Iterator<Item = String>, mut f: F) -> Vec<Issue>
+where
+    F: for<'a> FnMut(&mut Cursor<'a>) -> ParseResult<T>,
+{
+    input.enumerate()
+        .filter_map(|(i, text)| parse_line(&text, i + 1, &mut f).err())
+        .map(Issue::from)
+        .collect()
+}
+
+/// A run of consecutive non-blank lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// line number of the first line in the block.
+    pub first_line: usize,
+    pub lines: Vec<String>,
+}
+
+impl Block {
+    /// each line in the block, with its line number.
+    pub fn numbered(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.lines.iter()
+            .enumerate()
+            .map(|(i, l)| (self.first_line + i, l.as_str()))
+    }
+
+    /// an error for something missing from the block, reported at its first line.
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError { line: self.first_line, column: 1, message: message.into() }
+    }
+}
+
+/// splits `input` into blocks separated by (one or more) blank lines.
+pub fn blocks(input: impl Iterator<Item = String>) -> impl Iterator<Item = Block> {
+    let mut input = input.enumerate().peekable();
+
+    std::iter::from_fn(move || {
+        while input.next_if(|(_, line)| line.trim().is_empty()).is_some() {}
+
+        let (i, first) = input.next()?;
+        let mut lines = vec![first];
+        while let Some((_, line)) = input.next_if(|(_, line)| !line.trim().is_empty()) {
+            lines.push(line);
+        }
+        Some(Block { first_line: i + 1, lines })
+    })
+}
+
+
+#[test]
+fn test_cursor() {
+    let mut c = Cursor::new("move 12 from -3 to x", 7);
+    assert_eq!(c.word(), Ok("move"));
+    assert_eq!(c.whitespace(), 1);
+    assert_eq!(c.integer::<u32>(), Ok(12));
+    c.literal(" from ").unwrap();
+    assert_eq!(c.integer::<i32>(), Ok(-3));
+    assert!(c.try_literal(" to "));
+    assert!(!c.try_literal("y"));
+    assert_eq!(c.char(), Ok('x'));
+    assert!(c.end().is_ok());
+
+    let mut c = Cursor::new("add -1", 2);
+    c.literal("add ").unwrap();
+    assert_eq!(c.integer::<u8>(), Err(ParseError { line: 2, column: 5, message: "'-1' is out of range".into() }));
+}
+
+#[test]
+fn test_combinators() {
+    let items = parse_line("79, 98, 3", 1, |c| c.separated(", ", |c| c.integer::<u64>()));
+    assert_eq!(items, Ok(vec![79, 98, 3]));
+
+    let err = parse_line("79, x", 1, |c| c.separated(", ", |c| c.integer::<u64>())).unwrap_err();
+    assert_eq!((err.column, err.message.as_str()), (5, "expected an integer, found 'x'"));
+
+    let err = parse_line("B Q", 4, |c| {
+        c.one_of(&[("A", 1), ("B", 2)])?;
+        c.literal(" ")?;
+        c.one_of(&[("X", 1), ("Y", 2)])
+    }).unwrap_err();
+    assert_eq!(err.to_string(), "line 4, column 3: expected one of 'X', 'Y', found 'Q'");
+
+    let err = parse_line("12 extra", 1, |c| c.integer::<u32>()).unwrap_err();
+    assert_eq!(err.message, "unexpected ' extra'");
+
+    let mut c = Cursor::new("noop", 1);
+    assert_eq!(c.attempt(|c| { c.literal("no")?; c.literal("pe") }), None);
+    assert_eq!(c.rest(), "noop");
+}
+
+#[test]
+fn test_structure() {
+    let input = ["a", "b", "", "", "c", "", "d 1"].into_iter().map(String::from);
+    let blocks: Vec<Block> = blocks(input).collect();
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[1], Block { first_line: 5, lines: vec!["c".into()] });
+    assert_eq!(blocks[2].numbered().collect::<Vec<_>>(), vec![(7, "d 1")]);
+
+    let input = ["1", "", "x", "3"].into_iter().map(String::from);
+    let parsed: Vec<ParseResult<u32>> = lines(input, |c| c.integer()).collect();
+    assert_eq!(parsed[0], Ok(1));
+    assert_eq!(parsed[1].as_ref().unwrap_err().line, 3);
+    assert_eq!(parsed[2], Ok(3));
+}
+
+#[test]
+fn test_partial_input() {
+    // ie: the lines after a 3 line header & its blank separator
+    let input = ["1", "", "x", "3"].into_iter().map(String::from);
+    let parsed: Vec<ParseResult<u32>> = lines_from(input, 5, |c| c.integer()).collect();
+    assert_eq!(parsed[1].as_ref().unwrap_err().to_string(), "line 7, column 1: expected an integer, found 'x'");
+
+    let input = ["1", "", "x", "3"].into_iter().map(String::from);
+    assert_eq!(valid_lines(input, |c| c.integer::<u32>()).collect::<Vec<_>>(), vec![1, 3]);
+}
@@ -0,0 +1,386 @@
+//! Empirical complexity checks: generates inputs at growing sizes, times each solver part,
+//! and fits `time ~ size^k` to catch solvers that scale worse than they should.
+
+use std::{fmt::Write, time::{Duration, Instant}};
+
+use crate::{Day, Part, Solve};
+
+/// how far above the expected exponent a fit can be before it's flagged.
+const TOLERANCE: f64 = 0.2;
+
+/// runs that are faster than this are mostly timer noise, so they're left out of the fit.
+const MIN_FIT_DURATION: Duration = Duration::from_micros(50);
+
+/// fewest sizes to fit over, any less and a single noisy run can swing the fit.
+const MIN_FIT_POINTS: usize = 3;
+
+/// small, deterministic xorshift64* generator, so every run measures the same inputs.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// a value in `lo..=hi`
+    fn range(&mut self, lo: usize, hi: usize) -> usize {
+        lo + (self.next() % (hi - lo + 1) as u64) as usize
+    }
+
+    fn pick<T: Copy>(&mut self, values: &[T]) -> T {
+        values[self.range(0, values.len() - 1)]
+    }
+}
+
+/// the growth we'd expect (as an exponent of the input size) from a reasonable solver for each day.
+fn expected_exponent(day: &Day) -> Option<f64> {
+    match day {
+        Day::Day01 | Day::Day02 | Day::Day03 | Day::Day04 | Day::Day05 |
+        Day::Day06 | Day::Day07 | Day::Day08 | Day::Day09 | Day::Day10 |
+        Day::Day11 => Some(1.0),
+        _ => None,
+    }
+}
+
+/// generates an input for `day` that's roughly `factor` times the size of the base (1x) input.
+pub(crate) fn generate(day: &Day, factor: usize, rng: &mut Rng) -> Option<Vec<String>> {
+    let lines = match day {
+        Day::Day01 => (0..64 * factor)
+            .flat_map(|_| {
+                let mut group: Vec<String> = (0..rng.range(1, 8))
+                    .map(|_| rng.range(1_000, 60_000).to_string())
+                    .collect();
+                group.push(String::new());
+                group
+            })
+            .collect(),
+        Day::Day02 => (0..256 * factor)
+            .map(|_| format!("{} {}", rng.pick(&['A', 'B', 'C']), rng.pick(&['X', 'Y', 'Z'])))
+            .collect(),
+        Day::Day03 => (0..96 * factor)
+            .map(|_| {
+                let len = rng.range(4, 24) * 2;
+                (0..len).map(|_| rng.pick(LETTERS)).collect()
+            })
+            .collect(),
+        Day::Day04 => (0..256 * factor)
+            .map(|_| {
+                let (a, b) = (rng.range(1, 99), rng.range(1, 99));
+                let (c, d) = (rng.range(1, 99), rng.range(1, 99));
+                format!("{}-{},{}-{}", a.min(b), a.max(b), c.min(d), c.max(d))
+            })
+            .collect(),
+        Day::Day05 => {
+            const STACKS: usize = 9;
+            const HEIGHT: usize = 8;
+
+            let mut lines: Vec<String> = (0..HEIGHT)
+                .map(|_| {
+                    (0..STACKS)
+                        .map(|_| format!("[{}]", rng.pick(&LETTERS[26..])))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect();
+            lines.push((1..=STACKS).map(|i| format!(" {} ", i)).collect::<Vec<_>>().join(" "));
+            lines.push(String::new());
+
+            lines.extend((0..128 * factor).map(|_| {
+                let from = rng.range(1, STACKS);
+                let to = (from + rng.range(0, STACKS - 2)) % STACKS + 1;
+                format!("move {} from {} to {}", rng.range(1, 4), from, to)
+            }));
+            lines
+        },
+        Day::Day06 => {
+            // never 4 distinct characters in a row, so the marker is only found at the very end.
+            let mut stream: String = (0..1024 * factor).map(|_| rng.pick(&['a', 'b', 'c'])).collect();
+            stream.push_str("defghijklmnopq");
+            vec![stream]
+        },
+        Day::Day07 => {
+            // a random walk through the directory tree, so the depth grows along with the size.
+            let mut lines: Vec<String> = vec!["$ cd /".into()];
+            let mut depth = 0_usize;
+            for i in 0..48 * factor {
+                lines.push("$ ls".into());
+                lines.push(format!("dir d{}", i));
+                if i == 0 {
+                    // part 2 needs the disk to be short of space, the puzzle's always is
+                    lines.push("41000000 base.img".into());
+                }
+                // files shrink as the tree grows, so the disk never holds more than the puzzle's 70000000
+                for f in 0..rng.range(1, 3) {
+                    lines.push(format!("{} f{}.txt", rng.range(1, (300_000 / factor).max(1)), f));
+                }
+
+                if depth > 0 && rng.range(0, 2) == 0 {
+                    lines.push("$ cd ..".into());
+                    depth -= 1;
+                } else {
+                    lines.push(format!("$ cd d{}", i));
+                    depth += 1;
+                }
+            }
+            lines
+        },
+        Day::Day08 => {
+            let side = (16.0 * (factor as f64).sqrt()).round() as usize;
+            (0..side)
+                .map(|_| (0..side).map(|_| char::from(b'0' + rng.range(0, 9) as u8)).collect())
+                .collect()
+        },
+        Day::Day09 => (0..128 * factor)
+            .map(|_| format!("{} {}", rng.pick(&['U', 'D', 'L', 'R']), rng.range(1, 10)))
+            .collect(),
+        Day::Day10 => (0..256 * factor)
+            .map(|_| match rng.range(0, 2) {
+                0 => "noop".to_string(),
+                _ => format!("addx {}", rng.range(0, 10) as isize - 5),
+            })
+            .collect(),
+        Day::Day11 => {
+            const MONKEYS: usize = 4;
+            const DIVISORS: [u64; MONKEYS] = [2, 3, 5, 7];
+
+            (0..MONKEYS)
+                .flat_map(|m| {
+                    let items: Vec<String> = (0..factor)
+                        .map(|_| rng.range(50, 99).to_string())
+                        .collect();
+                    // part 1 doesn't reduce worry levels (other than dividing by 3), so they can't be allowed to grow:
+                    // no `old * old`, and never multiplying by more than the 3 they're divided by afterwards.
+                    let operation = match rng.range(0, 1) {
+                        0 => format!("old * {}", rng.range(2, 3)),
+                        _ => format!("old + {}", rng.range(2, 9)),
+                    };
+                    [
+                        format!("Monkey {}:", m),
+                        format!("  Starting items: {}", items.join(", ")),
+                        format!("  Operation: new = {}", operation),
+                        format!("  Test: divisible by {}", DIVISORS[m]),
+                        format!("    If true: throw to monkey {}", (m + 1) % MONKEYS),
+                        format!("    If false: throw to monkey {}", (m + 2) % MONKEYS),
+                        String::new(),
+                    ]
+                })
+                .collect()
+        },
+        _ => return None,
+    };
+
+    Some(lines)
+}
+
+const LETTERS: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm',
+    'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M',
+    'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+];
+
+/// fits `y = a + k*x` by least squares, returning `k`.
+fn fit_slope(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let cov: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let var: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+
+    if var == 0.0 { None } else { Some(cov / var) }
+}
+
+/// fits the growth exponent `k` of `time ~ factor^k`, ignoring runs too fast to measure reliably.
+/// only the larger half of the sizes (but at least `MIN_FIT_POINTS`) is used,
+/// since constant overheads hide the growth at small sizes.
+fn fit_exponent(timings: &[(usize, Duration)]) -> Option<f64> {
+    let points: Vec<(f64, f64)> = timings.iter()
+        .filter(|(_, t)| *t >= MIN_FIT_DURATION)
+        .map(|(factor, t)| ((*factor as f64).ln(), t.as_secs_f64().ln()))
+        .collect();
+
+    let skip = (points.len() / 2).min(points.len().saturating_sub(MIN_FIT_POINTS));
+    fit_slope(&points[skip..])
+}
+
+/// a solver under measurement, ie: a day's [`Solve::solve`].
+type Solver<'a> = &'a dyn Fn(Box<dyn Iterator<Item = String>>, Part) -> String;
+
+fn time_solver(solver: Solver, part: &Part, input: &[String], repeats: usize) -> Duration {
+    (0..repeats.max(1))
+        .map(|_| {
+            // copy the input before starting the clock, so only the solver is timed.
+            let lines: Vec<String> = input.to_vec();
+            let buf = Box::new(lines.into_iter());
+            let start = Instant::now();
+            solver(buf, part.clone());
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// The measured growth of a single solver part.
+pub struct PartScaling {
+    pub part: Part,
+    pub timings: Vec<(usize, Duration)>,
+    pub exponent: Option<f64>,
+    pub expected: f64,
+}
+
+impl PartScaling {
+    pub fn is_worse_than_expected(&self) -> bool {
+        self.exponent.is_some_and(|k| k > self.expected + TOLERANCE)
+    }
+}
+
+/// times both parts of `day` on generated inputs of size 1x, 2x, 4x ... `max_factor`x.
+/// returns `None` if there's no input generator for `day` yet.
+pub fn measure(day: &Day, max_factor: usize, repeats: usize) -> Option<(Vec<usize>, Vec<PartScaling>)> {
+    let expected = expected_exponent(day)?;
+
+    let factors: Vec<usize> = std::iter::successors(Some(1_usize), |f| f.checked_mul(2))
+        .take_while(|f| *f <= max_factor.max(1))
+        .collect();
+
+    let mut rng = Rng::new(2022);
+    let inputs: Vec<Vec<String>> = factors.iter()
+        .map(|f| generate(day, *f, &mut rng))
+        .collect::<Option<_>>()?;
+
+    let lines = inputs.iter().map(|i| i.len()).collect();
+    let parts = measure_parts(&|buf, part| day.solve(buf, part), &factors, &inputs, expected, repeats);
+
+    Some((lines, parts))
+}
+
+/// times both parts of `solver` on each of `inputs` (generated at the matching `factors`).
+fn measure_parts(solver: Solver, factors: &[usize], inputs: &[Vec<String>], expected: f64, repeats: usize) -> Vec<PartScaling> {
+    [Part::Part1, Part::Part2].into_iter()
+        .map(|part| {
+            let timings: Vec<(usize, Duration)> = factors.iter()
+                .zip(inputs.iter())
+                .map(|(f, input)| (*f, time_solver(solver, &part, input, repeats)))
+                .collect();
+            let exponent = fit_exponent(&timings);
+
+            PartScaling { part, timings, exponent, expected }
+        })
+        .collect()
+}
+
+/// renders the table of timings & fitted exponents from [`measure`].
+pub fn report(lines: &[usize], parts: &[PartScaling]) -> String {
+    let mut out = String::new();
+
+    let _ = write!(out, "{:>8}  {:>8}", "factor", "lines");
+    for p in parts {
+        let _ = write!(out, "  {:>12}", format!("{:?}", p.part));
+    }
+    out.push('\n');
+
+    for (row, line_count) in lines.iter().enumerate() {
+        let factor = parts.first().map(|p| p.timings[row].0).unwrap_or_default();
+        let _ = write!(out, "{:>8}  {:>8}", format!("{}x", factor), line_count);
+        for p in parts {
+            let _ = write!(out, "  {:>12}", format!("{:.2?}", p.timings[row].1));
+        }
+        out.push('\n');
+    }
+    out.push('\n');
+
+    for p in parts {
+        let _ = match p.exponent {
+            Some(k) => write!(out, "{:?}: time ~ n^{:.2} (expected n^{:.2})", p.part, k, p.expected),
+            None => write!(out, "{:?}: too fast to fit, try a larger --max-factor", p.part),
+        };
+        if p.is_worse_than_expected() {
+            out.push_str("  <-- WORSE THAN EXPECTED");
+        }
+        out.push('\n');
+    }
+
+    out.trim_end().to_string()
+}
+
+
+#[test]
+fn test_fit_slope() {
+    let points: Vec<(f64, f64)> = (1..10)
+        .map(|x| (x as f64, 3.0 + 2.0 * x as f64))
+        .collect();
+    let k = fit_slope(&points).unwrap();
+    assert!((k - 2.0).abs() < 1e-9);
+
+    assert_eq!(fit_slope(&[(1.0, 1.0)]), None);
+}
+
+#[test]
+fn test_fit_exponent_ignores_noise() {
+    let timings: Vec<(usize, Duration)> = [1_usize, 2, 4, 8, 16, 32, 64, 128]
+        .into_iter()
+        .map(|f| (f, Duration::from_micros(10 * (f * f) as u64 + 1000)))
+        .collect();
+
+    // the 1x run is under the noise floor, and the fixed overhead hides the growth at the
+    // smaller sizes, but the larger sizes are clearly quadratic.
+    let k = fit_exponent(&timings).unwrap();
+    assert!(k > 1.7 && k <= 2.0, "{}", k);
+}
+
+#[test]
+// a solver that compares every line against every other (like a naive day07 directory lookup)
+// should be caught by the same fit `scale` reports with.
+fn test_quadratic_solver_is_flagged() {
+    let quadratic = |buf: Box<dyn Iterator<Item = String>>, _: Part| {
+        let lines: Vec<String> = buf.collect();
+        let matches = lines.iter()
+            .flat_map(|a| lines.iter().map(move |b| std::hint::black_box(a == b)))
+            .filter(|m| *m)
+            .count();
+        matches.to_string()
+    };
+
+    let mut rng = Rng::new(7);
+    let factors = [1, 2, 4, 8];
+    let inputs: Vec<Vec<String>> = factors.iter()
+        .map(|f| generate(&Day::Day07, *f, &mut rng).expect("day07 has a generator"))
+        .collect();
+
+    let parts = measure_parts(&quadratic, &factors, &inputs, expected_exponent(&Day::Day07).unwrap(), 3);
+    for p in parts {
+        assert!(p.is_worse_than_expected(), "{:?} fit n^{:?}", p.part, p.exponent);
+    }
+}
+
+#[test]
+// every generated input should pass its day's validation, and solve without panicking.
+fn test_generated_inputs_are_valid() {
+    use crate::Validate;
+
+    let mut rng = Rng::new(7);
+    for day in [Day::Day01, Day::Day02, Day::Day03, Day::Day04, Day::Day05, Day::Day06,
+                Day::Day07, Day::Day08, Day::Day09, Day::Day10, Day::Day11] {
+        for factor in [1, 2, 4] {
+            let input = generate(&day, factor, &mut rng).expect("days 1-11 have generators");
+            let issues = day.validate(Box::new(input.clone().into_iter()));
+            assert!(issues.is_empty(), "{:?}", issues);
+
+            for part in [Part::Part1, Part::Part2] {
+                day.solve(Box::new(input.clone().into_iter()), part);
+            }
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Shortest path searches (and flood fills) over implicit graphs.
+//!
+//! Graphs are never built up front: each search takes the start state(s) and a
+//! closure listing a state's neighbours, so a state can be anything hashable
+//! (ie: a `Coord`, or `(Coord, minute)` when the map changes over time).
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+/// The outcome of a successful search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<S> {
+    /// total cost of the path (the number of steps, for `bfs`).
+    pub cost: usize,
+    /// every state from the start to the goal, inclusive.
+    pub path: Vec<S>,
+}
+
+/// every state seen so far, with the index of the state it was reached from.
+struct Visited<S> {
+    states: Vec<S>,
+    parents: Vec<Option<usize>>,
+    index: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash> Visited<S> {
+    fn new() -> Self {
+        Self { states: Vec::new(), parents: Vec::new(), index: HashMap::new() }
+    }
+
+    fn get(&self, state: &S) -> Option<usize> {
+        self.index.get(state).copied()
+    }
+
+    fn insert(&mut self, state: S, parent: Option<usize>) -> usize {
+        let i = self.states.len();
+        self.index.insert(state.clone(), i);
+        self.states.push(state);
+        self.parents.push(parent);
+        i
+    }
+
+    fn path_to(&self, mut i: usize) -> Vec<S> {
+        let mut path = vec![self.states[i].clone()];
+        while let Some(parent) = self.parents[i] {
+            path.push(self.states[parent].clone());
+            i = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// breadth first search, where every step costs 1.
+/// any of `starts` may begin the path, so a search from "every `a` square" is a single call.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = Visited::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if visited.get(&start).is_none() {
+            queue.push_back((visited.insert(start, None), 0));
+        }
+    }
+
+    while let Some((i, steps)) = queue.pop_front() {
+        if is_goal(&visited.states[i]) {
+            return Some(Found { cost: steps, path: visited.path_to(i) });
+        }
+
+        for next in neighbours(&visited.states[i]) {
+            if visited.get(&next).is_none() {
+                queue.push_back((visited.insert(next, Some(i)), steps + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// every state reachable from `starts` (including them), ie: a flood fill.
+pub fn reachable<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+) -> HashSet<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut seen: HashSet<S> = HashSet::new();
+    let mut stack: Vec<S> = Vec::new();
+
+    for start in starts {
+        if seen.insert(start.clone()) {
+            stack.push(start);
+        }
+    }
+
+    while let Some(state) = stack.pop() {
+        for next in neighbours(&state) {
+            if seen.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+
+    seen
+}
+
+/// shortest path where each neighbour comes with the cost of stepping to it.
+pub fn dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    astar(starts, neighbours, |_| 0, is_goal)
+}
+
+/// dijkstra, guided by `heuristic`: an estimate of the remaining cost from a state.
+/// the heuristic must never overestimate, or the path found may not be the shortest.
+pub fn astar<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut visited = Visited::new();
+    let mut best: Vec<usize> = Vec::new();
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        if visited.get(&start).is_none() {
+            let estimate = heuristic(&start);
+            queue.push(Reverse((estimate, 0, visited.insert(start, None))));
+            best.push(0);
+        }
+    }
+
+    while let Some(Reverse((_, cost, i))) = queue.pop() {
+        if cost > best[i] {
+            // a cheaper way here was already expanded
+            continue;
+        }
+        if is_goal(&visited.states[i]) {
+            return Some(Found { cost, path: visited.path_to(i) });
+        }
+
+        for (next, step_cost) in neighbours(&visited.states[i]) {
+            let next_cost = cost + step_cost;
+            let j = match visited.get(&next) {
+                Some(j) if best[j] <= next_cost => continue,
+                Some(j) => {
+                    visited.parents[j] = Some(i);
+                    best[j] = next_cost;
+                    j
+                },
+                None => {
+                    best.push(next_cost);
+                    visited.insert(next, Some(i))
+                },
+            };
+            let estimate = next_cost + heuristic(&visited.states[j]);
+            queue.push(Reverse((estimate, next_cost, j)));
+        }
+    }
+
+    None
+}
+
+
+#[cfg(test)]
+use crate::grid::{Coord, Grid};
+
+#[cfg(test)]
+fn test_maze() -> Grid<char> {
+    let lines = [
+        "S..#....",
+        ".#.#.##.",
+        ".#...#..",
+        ".####.#.",
+        "......#E",
+    ].into_iter().map(String::from);
+
+    Grid::parse(lines, Some).unwrap()
+}
+
+#[test]
+fn test_bfs() {
+    let maze = test_maze();
+    let start = maze.find(|c| *c == 'S').unwrap();
+    let end = maze.find(|c| *c == 'E').unwrap();
+    let open = |c: &Coord| maze.neighbours4(*c).filter(|n| maze[*n] != '#').collect::<Vec<_>>();
+
+    let found = bfs([start], open, |c| *c == end).unwrap();
+    assert_eq!(found.cost, 15);
+    assert_eq!(found.path.len(), 16);
+    assert_eq!(found.path.first(), Some(&start));
+    assert_eq!(found.path.last(), Some(&end));
+    assert!(found.path.windows(2).all(|w| w[0].manhattan_dist(&w[1]) == 1));
+
+    // starting from every open square on the right hand edge
+    let starts = (0..5).map(|y| Coord::new(7, y)).filter(|c| maze[*c] != '#');
+    assert_eq!(bfs(starts, open, |c| *c == end).unwrap().cost, 0);
+
+    assert_eq!(bfs([start], open, |c| *c == Coord::new(3, 0)), None);
+}
+
+#[test]
+fn test_reachable() {
+    let maze = test_maze();
+    let start = maze.find(|c| *c == 'S').unwrap();
+    let open = |c: &Coord| maze.neighbours4(*c).filter(|n| maze[*n] != '#').collect::<Vec<_>>();
+
+    let flooded = reachable([start], open);
+    assert!(flooded.contains(&start));
+    assert!(flooded.contains(&maze.find(|c| *c == 'E').unwrap()));
+    assert!(!flooded.contains(&Coord::new(3, 0)));
+    assert!(flooded.iter().all(|c| maze[*c] != '#'));
+}
+
+#[test]
+fn test_weighted() {
+    // 0 -> 1 -> 3 is shorter by steps, but 0 -> 2 -> 3 is cheaper
+    let edges = |n: &u32| match n {
+        0 => vec![(1, 5), (2, 1)],
+        1 => vec![(3, 1)],
+        2 => vec![(3, 2)],
+        _ => vec![],
+    };
+
+    let found = dijkstra([0], edges, |n| *n == 3).unwrap();
+    assert_eq!(found, Found { cost: 3, path: vec![0, 2, 3] });
+
+    let maze = test_maze();
+    let start = maze.find(|c| *c == 'S').unwrap();
+    let end = maze.find(|c| *c == 'E').unwrap();
+    let open = |c: &Coord| maze.neighbours4(*c).filter(|n| maze[*n] != '#').map(|n| (n, 1)).collect::<Vec<_>>();
+    let found = astar([start], open, |c| c.manhattan_dist(&end), |c| *c == end).unwrap();
+    assert_eq!(found.cost, 15);
+}
+
+#[test]
+fn test_time_state() {
+    // a door at (1,0) is only open on even minutes, so the search has to wait for it
+    let door = Coord::new(1, 0);
+    let moves = |(c, t): &(Coord, usize)| {
+        [Coord::new(c.x + 1, c.y), *c]
+            .into_iter()
+            .filter(|n| n.x <= 2 && (*n != door || (t + 1) % 2 == 0))
+            .map(|n| (n, t + 1))
+            .collect::<Vec<_>>()
+    };
+
+    let found = bfs([(Coord::new(0, 0), 0)], moves, |(c, _)| c.x == 2).unwrap();
+    assert_eq!(found.cost, 3);
+    assert_eq!(found.path.iter().map(|(_, t)| *t).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+}
@@ -0,0 +1,56 @@
+use std::fmt::Display;
+
+/// A single problem found while checking a puzzle input against a day's grammar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    /// 1-based line number the problem was found on.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Issue {
+    pub(crate) fn new(line: usize, message: impl Into<String>) -> Self {
+        Self { line, message: message.into() }
+    }
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("line {}: {}", self.line, self.message))
+    }
+}
+
+/// checks every line independently, `check` returns a message for any line it rejects.
+pub(crate) fn check_lines<F>(input: Box<dyn Iterator<Item = String>>, mut check: F) -> Vec<Issue>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    input.enumerate()
+        .filter_map(|(i, line)| {
+            check(line.as_str()).map(|msg| Issue::new(i + 1, msg))
+        })
+        .collect()
+}
+
+/// runs a day's `validate` over `lines`, checking which lines it reported (in order).
+/// returns the issues, for tests that check their messages too.
+#[cfg(test)]
+pub(crate) fn assert_issue_lines(validate: fn(Box<dyn Iterator<Item = String>>) -> Vec<Issue>, lines: &[&str], expected: &[usize]) -> Vec<Issue> {
+    let input: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+
+    let issues = validate(Box::new(input.into_iter()));
+    let issue_lines: Vec<usize> = issues.iter().map(|i| i.line).collect();
+    assert_eq!(issue_lines, expected, "issues: {:?}", issues);
+    issues
+}
+
+#[test]
+// days without a solver yet report that, rather than panicking
+fn test_unsolved_days() {
+    use crate::{Day, Validate};
+
+    for day in [Day::Day24, Day::Day25] {
+        let issues = day.validate(Box::new(std::iter::empty()));
+        assert_eq!(issues, vec![Issue::new(1, "there's no solver for this day yet")]);
+    }
+}